@@ -32,6 +32,12 @@ impl BlockList {
         }
     }
 
+    /// Adds the given digits to the blocklist of the given position,
+    /// e.g. the odd digits of a position shaded as even
+    pub fn add_position_block_list(&mut self, position: usize, digits: &HashSet<usize>) {
+        self.block_list.entry(position).or_default().extend(digits);
+    }
+
     /// Checks the given options of a not yet blocklisted (one dimensional) group
     /// if only the same digits are valid, and if so updates the blocklist
 
@@ -164,5 +170,12 @@ mod kk_block_list_tests {
         assert_eq!(block_list.get_position_block_list(&13).len(), 3);
         assert_eq!(block_list.get_position_block_list(&67).len(), 4);
         assert_eq!(block_list.get_position_block_list(&17).len(), 6); //3 + 4 -1
+
+        //the digits of a single position are joined with the digits of its row
+        let odd_digits: HashSet<usize> = vec![1, 3, 5].into_iter().collect();
+        block_list.add_position_block_list(13, &odd_digits);
+        assert_eq!(block_list.get_position_block_list(&13).len(), 4); //3 + 3 -2
+        block_list.add_position_block_list(1, &odd_digits);
+        assert_eq!(block_list.get_position_block_list(&1), odd_digits);
     }
 }
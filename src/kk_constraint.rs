@@ -0,0 +1,724 @@
+//! The module kk_constraint is part of kenken_solve and provides the implementation
+//! of constraints between positions, which are checked in addition to the groups
+//!
+//! Other than a group, a constraint does not cover its positions, i.e. the digits of the
+//! positions are set by the groups and the constraint only restricts the valid options
+//! of these groups.
+//!
+//! The constraint list holds all constraints of a puzzle and maps each position to the
+//! constraints, the position is part of.
+//!
+
+use std::collections::HashMap;
+
+use crate::kk_puzzle::EMPTY;
+
+/// Steps of a knight's move, each move in one direction only
+pub const KNIGHT_MOVES: [(isize, isize); 4] = [(1, 2), (2, 1), (1, -2), (2, -1)];
+/// Steps of a king's move, each move in one direction only
+pub const KING_MOVES: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Parser of a single constraint line of a section, e.g. Constraint::new_inequality
+/// for the dimension of the puzzle
+pub type ConstraintParser<'a> = &'a dyn Fn(&str) -> Result<Constraint, String>;
+
+/// Struct constraint describes a single constraint
+/// A constraint consists of
+/// * the relation between the digits of the positions
+///     * '<' - the digit of the first position is less than the digit of the second position
+///     * 'v' - the count of visible skyscrapers along the positions is the result
+///     * 's' - the digits of the positions form a straight, i.e. a set of consecutive digits
+///     * '=' - the digits of both positions are equal (a shared position of two grids)
+///     * '!' - the digits of both positions are different (e.g. a knight's move apart)
+///     * 'w' - the digits of both neighbours are consecutive (white Kropki dot)
+///     * 'b' - one digit of both neighbours is the double of the other (black Kropki dot)
+///     * 'X' - the digits of both neighbours sum up to 10
+///     * 'V' - the digits of both neighbours sum up to 5
+///     * 'S' - the sum of the digits between the lowest and the highest digit of a complete row
+///       or column is the result (sandwich)
+///     * 't' - the digits strictly increase along the positions (thermometer, bulb first)
+///     * 'a' - the digit of the first position is the sum of the digits of the other positions
+///       (arrow, circle first)
+///     * 'p' - the digit of the single position is even (result 0) or odd (result 1),
+///       i.e. a shaded position of a parity map, whose digits are already filtered by the
+///       blocklist, hence the constraint only reports contradicting digits (see get_violations)
+/// * the result of the relation (0, if the relation has no result)
+/// * the (vector of) positions within the puzzle belonging to the constraint
+/// * the lowest and the highest digit of the puzzle, which are the crusts of a sandwich
+///   ((0, 0) for all other relations)
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct Constraint {
+    relation: char,
+    result: usize,
+    positions: Vec<usize>,
+    crusts: (usize, usize),
+}
+
+impl Constraint {
+    /// Creates a new inequality constraint between two neighbouring positions
+    /// Input:
+    ///  * dimension - the dimension of the puzzle
+    ///  * constraint_as_string - the constraint in the format
+    ///    ``` [position 1][relation][position 2] ```, e.g. "00<01" or "12>22"
+    ///
+    /// Returns: a result of
+    ///  * a new constraint, the relation '>' is stored as '<' with swapped positions
+    ///  * an error String, if the string can't be parsed or the positions are not neighbours
+    pub fn new_inequality(dimension: usize, constraint_as_string: &str) -> Result<Self, String> {
+        let (mut positions, relation) =
+            match parse_neighbours(dimension, constraint_as_string, &['<', '>']) {
+                Some(neighbours) => neighbours,
+                None => {
+                    return Err(format!(
+                        "Can't parse inequality or positions are no neighbours: {}",
+                        constraint_as_string
+                    ))
+                }
+            };
+        if relation == '>' {
+            positions.reverse();
+        }
+
+        Ok(Constraint {
+            relation: '<',
+            result: 0,
+            positions,
+            crusts: (0, 0),
+        })
+    }
+
+    /// Creates a new constraint for a clue between two neighbouring positions,
+    /// i.e. a Kropki dot or a XV clue
+    /// Input:
+    ///  * dimension - the dimension of the puzzle
+    ///  * constraint_as_string - the constraint in the format
+    ///    ``` [position 1][clue][position 2] ```, e.g. "00w01" or "12X22",
+    ///    the clue is one of 'w' (white dot), 'b' (black dot), 'X' or 'V'
+    ///
+    /// Returns: a result of
+    ///  * a new constraint with the clue as relation
+    ///  * an error String, if the string can't be parsed or the positions are not neighbours
+    pub fn new_adjacent(dimension: usize, constraint_as_string: &str) -> Result<Self, String> {
+        match parse_neighbours(dimension, constraint_as_string, &['w', 'b', 'X', 'V']) {
+            Some((positions, relation)) => Ok(Constraint {
+                relation,
+                result: 0,
+                positions,
+                crusts: (0, 0),
+            }),
+            None => Err(format!(
+                "Can't parse adjacent clue or positions are no neighbours: {}",
+                constraint_as_string
+            )),
+        }
+    }
+
+    /// Creates a new skyscraper constraint for a clue outside the grid
+    /// Input:
+    ///  * clue - the count of skyscrapers visible from the clue
+    ///  * positions - the complete row or column in viewing direction,
+    ///    i.e. starting with the position next to the clue
+    ///
+    /// The digit of a position is the height of its skyscraper,
+    /// a skyscraper is visible if it is higher than all skyscrapers in front of it.
+    pub fn new_skyscraper(clue: usize, positions: Vec<usize>) -> Self {
+        Constraint {
+            relation: 'v',
+            result: clue,
+            positions,
+            crusts: (0, 0),
+        }
+    }
+
+    /// Creates a new straight constraint for a compartment of a Str8ts,
+    /// i.e. the open and given white positions between two black positions of a row or column
+    pub fn new_straight(positions: Vec<usize>) -> Self {
+        Constraint {
+            relation: 's',
+            result: 0,
+            positions,
+            crusts: (0, 0),
+        }
+    }
+
+    /// Creates a new equality constraint between the positions of a cell
+    /// shared by two grids of a multi grid Sudoku
+    pub fn new_equality(first: usize, second: usize) -> Self {
+        Constraint {
+            relation: '=',
+            result: 0,
+            positions: vec![first, second],
+            crusts: (0, 0),
+        }
+    }
+
+    /// Creates a new sandwich constraint for a clue outside the grid
+    /// Input:
+    ///  * digits - the digits of the puzzle, the dimension is the count of digits
+    ///  * constraint_as_string - the clue in the format ``` [r|c][index]=[sum] ```,
+    ///    e.g. "r0=15" for the first row or "c8=0" for the last column of a Sudoku
+    ///
+    /// Returns: a result of
+    ///  * a new constraint for the complete row or column, the sum of the digits
+    ///    between the lowest and the highest digit of the puzzle (the crusts) is the result
+    ///  * an error String, if the string can't be parsed
+    pub fn new_sandwich(digits: &[usize], constraint_as_string: &str) -> Result<Self, String> {
+        let error = Err(format!("Can't parse sandwich clue: {}", constraint_as_string));
+        let dimension = digits.len();
+
+        let (line, sum) = match constraint_as_string.split_once('=') {
+            Some((line, sum)) => (line.trim(), sum.trim()),
+            None => return error,
+        };
+        let index = match line.get(1..).map(|index| index.parse::<usize>()) {
+            Some(Ok(index)) if index < dimension => index,
+            _ => return error,
+        };
+        let positions: Vec<usize> = match line.chars().next() {
+            Some('r') => (0..dimension).map(|column| index * 10 + column).collect(),
+            Some('c') => (0..dimension).map(|row| row * 10 + index).collect(),
+            _ => return error,
+        };
+        let crusts = match (digits.iter().min(), digits.iter().max()) {
+            (Some(&lowest), Some(&highest)) => (lowest, highest),
+            _ => return error,
+        };
+
+        match sum.parse::<usize>() {
+            Ok(result) => Ok(Constraint {
+                relation: 'S',
+                result,
+                positions,
+                crusts,
+            }),
+            Err(_) => error,
+        }
+    }
+
+    /// Creates a new thermometer constraint
+    /// Input:
+    ///  * dimension - the dimension of the puzzle
+    ///  * constraint_as_string - the positions of the thermometer starting with the bulb,
+    ///    separated by '.', e.g. "00.01.12"
+    ///
+    /// Returns: a result of
+    ///  * a new constraint, the digits strictly increase from the bulb to the tip
+    ///  * an error String, if the string can't be parsed or the positions are no path
+    pub fn new_thermometer(dimension: usize, constraint_as_string: &str) -> Result<Self, String> {
+        match parse_path(dimension, constraint_as_string) {
+            Some(positions) => Ok(Constraint {
+                relation: 't',
+                result: 0,
+                positions,
+                crusts: (0, 0),
+            }),
+            None => Err(format!("Can't parse thermometer: {}", constraint_as_string)),
+        }
+    }
+
+    /// Creates a new arrow constraint
+    /// Input:
+    ///  * dimension - the dimension of the puzzle
+    ///  * constraint_as_string - the positions of the arrow starting with the circle,
+    ///    separated by '.', e.g. "00.01.12"
+    ///
+    /// Returns: a result of
+    ///  * a new constraint, the digit of the circle is the sum of the digits along the arrow
+    ///  * an error String, if the string can't be parsed or the positions are no path
+    pub fn new_arrow(dimension: usize, constraint_as_string: &str) -> Result<Self, String> {
+        match parse_path(dimension, constraint_as_string) {
+            Some(positions) => Ok(Constraint {
+                relation: 'a',
+                result: 0,
+                positions,
+                crusts: (0, 0),
+            }),
+            None => Err(format!("Can't parse arrow: {}", constraint_as_string)),
+        }
+    }
+
+    /// Creates a new parity constraint for a shaded position of a parity map
+    /// Input:
+    ///  * position - the shaded position
+    ///  * remainder - the remainder (modulo 2) of the digits allowed, i.e. 0 for even and 1 for odd digits
+    pub fn new_parity(position: usize, remainder: usize) -> Self {
+        Constraint {
+            relation: 'p',
+            result: remainder,
+            positions: vec![position],
+            crusts: (0, 0),
+        }
+    }
+
+    /// Creates the constraints between all positions of a dimension x dimension grid,
+    /// which are a chess move apart, i.e. which must hold different digits
+    /// Input:
+    ///  * dimension - the dimension of the grid
+    ///  * grid_offset - the offset of the positions of the grid (100 per grid of a multi grid Sudoku)
+    ///  * moves - the (row, column) steps of the chess piece, each move in one direction only
+    pub fn new_chess_moves(dimension: usize, grid_offset: usize, moves: &[(isize, isize)]) -> Vec<Self> {
+        let coordinates = 0..dimension as isize;
+        coordinates
+            .clone()
+            .flat_map(|row| coordinates.clone().map(move |column| (row, column)))
+            .flat_map(|(row, column)| {
+                moves
+                    .iter()
+                    .map(move |(row_step, column_step)| (row, column, row + row_step, column + column_step))
+            })
+            .filter(|(_, _, row, column)| coordinates.contains(row) && coordinates.contains(column))
+            .map(|(row_1, column_1, row_2, column_2)| Constraint {
+                relation: '!',
+                result: 0,
+                positions: vec![
+                    grid_offset + (row_1 * 10 + column_1) as usize,
+                    grid_offset + (row_2 * 10 + column_2) as usize,
+                ],
+                crusts: (0, 0),
+            })
+            .collect()
+    }
+
+    /// Checks the given digits of the positions of the constraint.
+    /// Positions without a digit (EMPTY) are not decided yet, hence the constraint
+    /// is only violated if the decided digits already contradict the relation.
+    pub fn is_satisfied(&self, digits: &[usize]) -> bool {
+        match self.relation {
+            '<' => digits[0] == EMPTY || digits[1] == EMPTY || digits[0] < digits[1],
+            '=' => digits[0] == EMPTY || digits[1] == EMPTY || digits[0] == digits[1],
+            '!' => digits[0] == EMPTY || digits[1] == EMPTY || digits[0] != digits[1],
+            'p' => digits[0] == EMPTY || digits[0] % 2 == self.result,
+            'w' | 'b' | 'X' | 'V' if digits.contains(&EMPTY) => true,
+            'w' => digits[0].abs_diff(digits[1]) == 1,
+            'b' => digits[0] == 2 * digits[1] || digits[1] == 2 * digits[0],
+            'X' => digits[0] + digits[1] == 10,
+            'V' => digits[0] + digits[1] == 5,
+            'S' => {
+                //the sandwich can only be checked, once both crusts are known,
+                //each open position of the filling holds at least the lowest digit + 1
+                let (lowest_digit, highest_digit) = self.crusts;
+                let lowest = digits.iter().position(|&digit| digit == lowest_digit);
+                let highest = digits.iter().position(|&digit| digit == highest_digit);
+                match (lowest, highest) {
+                    (Some(lowest), Some(highest)) => {
+                        let filling = &digits[lowest.min(highest) + 1..lowest.max(highest)];
+                        let sum: usize = filling.iter().filter(|&&digit| digit != EMPTY).sum();
+                        let open_count = filling.iter().filter(|&&digit| digit == EMPTY).count();
+                        if open_count > 0 {
+                            sum + open_count * (lowest_digit + 1) <= self.result
+                        } else {
+                            sum == self.result
+                        }
+                    }
+                    _ => true,
+                }
+            }
+            't' => {
+                //each step along the thermometer increases the digit by at least 1
+                let decided: Vec<(usize, usize)> = digits
+                    .iter()
+                    .copied()
+                    .enumerate()
+                    .filter(|&(_, digit)| digit != EMPTY)
+                    .collect();
+                decided
+                    .windows(2)
+                    .all(|pair| pair[1].1 >= pair[0].1 + pair[1].0 - pair[0].0)
+            }
+            'a' => {
+                let sum: usize = digits[1..].iter().filter(|&&digit| digit != EMPTY).sum();
+                if digits[0] == EMPTY {
+                    true
+                } else if digits[1..].contains(&EMPTY) {
+                    sum <= digits[0]
+                } else {
+                    sum == digits[0]
+                }
+            }
+            'v' => {
+                //count the visible skyscrapers in front of the first undecided position,
+                //once the highest skyscraper is reached no further one is visible
+                let mut visible = 0;
+                let mut highest = 0;
+                for &digit in digits.iter().take_while(|&&digit| digit != EMPTY) {
+                    if digit > highest {
+                        visible += 1;
+                        highest = digit;
+                    }
+                }
+                if highest == digits.len() || !digits.contains(&EMPTY) {
+                    visible == self.result
+                } else {
+                    visible < self.result
+                }
+            }
+            's' => {
+                //the decided digits must fit into a straight of the length of the compartment
+                let decided = digits.iter().filter(|&&digit| digit != EMPTY);
+                match (decided.clone().min(), decided.max()) {
+                    (Some(min), Some(max)) => max - min < digits.len(),
+                    _ => true,
+                }
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Parses two neighbouring positions separated by one of the given relations,
+/// e.g. "00<01", and returns the positions and the relation
+fn parse_neighbours(
+    dimension: usize,
+    constraint_as_string: &str,
+    relations: &[char],
+) -> Option<(Vec<usize>, char)> {
+    let relation_index = constraint_as_string.find(relations)?;
+    let relation = constraint_as_string[relation_index..].chars().next()?;
+    let first = constraint_as_string[..relation_index].trim().parse::<usize>().ok()?;
+    let second = constraint_as_string[relation_index + 1..].trim().parse::<usize>().ok()?;
+
+    let (row_1, column_1) = (first / 10, first % 10);
+    let (row_2, column_2) = (second / 10, second % 10);
+    let is_neighbour = row_1.abs_diff(row_2) + column_1.abs_diff(column_2) == 1;
+    if !is_neighbour || [row_1, column_1, row_2, column_2].iter().any(|&c| c >= dimension) {
+        return None;
+    }
+
+    Some((vec![first, second], relation))
+}
+
+/// Parses a path of positions separated by '.', e.g. "00.01.12",
+/// each position must be a neighbour (including diagonals) of the previous one
+fn parse_path(dimension: usize, constraint_as_string: &str) -> Option<Vec<usize>> {
+    let positions: Vec<usize> = constraint_as_string
+        .split('.')
+        .map(|position| position.trim().parse::<usize>().ok())
+        .collect::<Option<Vec<usize>>>()?;
+
+    let is_path = positions.len() >= 2
+        && positions.iter().all(|p| p / 10 < dimension && p % 10 < dimension)
+        && positions.windows(2).all(|pair| {
+            let row_distance = (pair[0] / 10).abs_diff(pair[1] / 10);
+            let column_distance = (pair[0] % 10).abs_diff(pair[1] % 10);
+            row_distance.max(column_distance) == 1
+        });
+
+    if is_path {
+        Some(positions)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConstraintList {
+    constraints: Vec<Constraint>,
+    position_constraints: HashMap<usize, Vec<usize>>,
+}
+
+impl ConstraintList {
+    /// Creates a new constraint list without any constraints
+    pub fn new() -> Self {
+        ConstraintList {
+            constraints: Vec::new(),
+            position_constraints: HashMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    /// Iterates over all constraints in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = &Constraint> {
+        self.constraints.iter()
+    }
+
+    /// Adds a constraint and registers it for all its positions,
+    /// a parity constraint isn't registered, as the blocklist filters the options by parity
+    pub fn add(&mut self, constraint: Constraint) {
+        let constraint_index = self.constraints.len();
+        let positions: &[usize] = match constraint.relation {
+            'p' => &[],
+            _ => &constraint.positions,
+        };
+        for &position in positions {
+            self.position_constraints
+                .entry(position)
+                .or_default()
+                .push(constraint_index);
+        }
+        self.constraints.push(constraint);
+    }
+
+    /// Retrieves all constraints violated by the digits of the given field
+    pub fn get_violations(&self, field: &[usize]) -> Vec<&Constraint> {
+        self.constraints
+            .iter()
+            .filter(|constraint| {
+                let digits: Vec<usize> = constraint.positions.iter().map(|&p| field[p]).collect();
+                !constraint.is_satisfied(&digits)
+            })
+            .collect()
+    }
+
+    /// Filters the options of a group, only options satisfying all constraints
+    /// of the group positions are kept.
+    /// Digits of positions outside the group are taken from the field.
+    pub fn filter_options(
+        &self,
+        positions: &[usize],
+        options: Vec<Vec<usize>>,
+        field: &[usize],
+    ) -> Vec<Vec<usize>> {
+        //get the constraints of the group positions (each constraint only once)
+        let mut constraint_indices: Vec<usize> = positions
+            .iter()
+            .filter_map(|position| self.position_constraints.get(position))
+            .flatten()
+            .copied()
+            .collect();
+        if constraint_indices.is_empty() {
+            return options;
+        }
+        constraint_indices.sort_unstable();
+        constraint_indices.dedup();
+
+        //for each constraint position either the index within the group option
+        //or the position in the field
+        let constraint_sources: Vec<(&Constraint, Vec<Result<usize, usize>>)> = constraint_indices
+            .iter()
+            .map(|&index| &self.constraints[index])
+            .map(|constraint| {
+                (
+                    constraint,
+                    constraint
+                        .positions
+                        .iter()
+                        .map(|p| match positions.iter().position(|gp| gp == p) {
+                            Some(option_index) => Ok(option_index),
+                            None => Err(*p),
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+
+        options
+            .into_iter()
+            .filter(|option| {
+                constraint_sources.iter().all(|(constraint, sources)| {
+                    let digits: Vec<usize> = sources
+                        .iter()
+                        .map(|source| match source {
+                            Ok(option_index) => option[*option_index],
+                            Err(position) => field[*position],
+                        })
+                        .collect();
+                    constraint.is_satisfied(&digits)
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod kk_constraint_tests {
+    use super::*;
+
+    #[test]
+    fn check_new_inequality() {
+        let constraint = Constraint::new_inequality(5, "00<01").unwrap();
+        assert_eq!(constraint.relation, '<');
+        assert_eq!(constraint.positions, vec!(0, 1));
+
+        let constraint = Constraint::new_inequality(5, "32>22").unwrap();
+        assert_eq!(constraint.positions, vec!(22, 32));
+
+        //no neighbours, out of range and parse errors
+        assert!(Constraint::new_inequality(5, "00<11").is_err());
+        assert!(Constraint::new_inequality(5, "44<45").is_err());
+        assert!(Constraint::new_inequality(5, "00=01").is_err());
+    }
+
+    #[test]
+    fn check_new_adjacent() {
+        let white_dot = Constraint::new_adjacent(9, "00w01").unwrap();
+        assert_eq!(white_dot.relation, 'w');
+        assert_eq!(white_dot.positions, vec!(0, 1));
+        assert!(white_dot.is_satisfied(&[4, 3]));
+        assert!(!white_dot.is_satisfied(&[4, 2]));
+
+        let black_dot = Constraint::new_adjacent(9, "12b22").unwrap();
+        assert!(black_dot.is_satisfied(&[3, 6]));
+        assert!(black_dot.is_satisfied(&[4, 2]));
+        assert!(!black_dot.is_satisfied(&[4, 3]));
+
+        let x = Constraint::new_adjacent(9, "33X34").unwrap();
+        assert!(x.is_satisfied(&[3, 7]));
+        assert!(!x.is_satisfied(&[3, 6]));
+        assert!(x.is_satisfied(&[3, EMPTY]));
+
+        let v = Constraint::new_adjacent(9, "33V43").unwrap();
+        assert!(v.is_satisfied(&[1, 4]));
+        assert!(!v.is_satisfied(&[1, 3]));
+
+        //no neighbours, out of range and unknown clues
+        assert!(Constraint::new_adjacent(9, "00X11").is_err());
+        assert!(Constraint::new_adjacent(4, "33V34").is_err());
+        assert!(Constraint::new_adjacent(9, "00<01").is_err());
+    }
+
+    #[test]
+    fn check_sandwich_thermometer_arrow() {
+        let sandwich = Constraint::new_sandwich(&[1, 2, 3, 4], "r2=5").unwrap();
+        assert_eq!(sandwich.positions, vec!(20, 21, 22, 23));
+        assert_eq!(sandwich.result, 5);
+        assert!(sandwich.is_satisfied(&[4, 3, 2, 1]));
+        assert!(!sandwich.is_satisfied(&[4, 2, 1, 3]));
+        assert!(sandwich.is_satisfied(&[4, EMPTY, EMPTY, 1]));
+        assert!(sandwich.is_satisfied(&[4, 3, EMPTY, EMPTY]));
+        let sandwich = Constraint::new_sandwich(&[1, 2, 3, 4], "c1=0").unwrap();
+        assert_eq!(sandwich.positions, vec!(1, 11, 21, 31));
+        assert!(sandwich.is_satisfied(&[2, 1, 4, 3]));
+        assert!(Constraint::new_sandwich(&[1, 2, 3, 4], "c4=0").is_err());
+        assert!(Constraint::new_sandwich(&[1, 2, 3, 4], "x1=0").is_err());
+
+        //the crusts of zero based digits are 0 and 3, the filling might hold no digit at all
+        let sandwich = Constraint::new_sandwich(&[0, 1, 2, 3], "r0=3").unwrap();
+        assert_eq!(sandwich.crusts, (0, 3));
+        assert!(sandwich.is_satisfied(&[3, 1, 2, 0]));
+        assert!(!sandwich.is_satisfied(&[3, 2, 0, 1]));
+        assert!(sandwich.is_satisfied(&[3, EMPTY, EMPTY, 0]));
+        assert!(sandwich.is_satisfied(&[3, 2, EMPTY, 0]));
+        let sandwich = Constraint::new_sandwich(&[0, 1, 2, 3], "r0=1").unwrap();
+        assert!(!sandwich.is_satisfied(&[3, 1, EMPTY, 0]));
+        assert!(sandwich.is_satisfied(&[3, EMPTY, 0, 1]));
+        let sandwich = Constraint::new_sandwich(&[0, 1, 2, 3], "c0=0").unwrap();
+        assert!(sandwich.is_satisfied(&[1, 0, 3, 2]));
+        assert!(sandwich.is_satisfied(&[0, 3, EMPTY, EMPTY]));
+
+        let thermometer = Constraint::new_thermometer(4, "22.13.02").unwrap();
+        assert_eq!(thermometer.positions, vec!(22, 13, 2));
+        assert!(thermometer.is_satisfied(&[2, 3, 4]));
+        assert!(thermometer.is_satisfied(&[1, EMPTY, 3]));
+        assert!(!thermometer.is_satisfied(&[2, EMPTY, 3]));
+        assert!(Constraint::new_thermometer(4, "22.02").is_err());
+
+        let arrow = Constraint::new_arrow(4, "21.30.31").unwrap();
+        assert!(arrow.is_satisfied(&[3, 1, 2]));
+        assert!(arrow.is_satisfied(&[3, EMPTY, 2]));
+        assert!(!arrow.is_satisfied(&[3, EMPTY, 4]));
+        assert!(!arrow.is_satisfied(&[4, 1, 2]));
+        assert!(Constraint::new_arrow(4, "21.30.x").is_err());
+    }
+
+    #[test]
+    fn check_skyscraper() {
+        let constraint = Constraint::new_skyscraper(3, vec![0, 10, 20, 30]);
+        assert_eq!(constraint.relation, 'v');
+        assert_eq!(constraint.result, 3);
+
+        //complete columns
+        assert!(constraint.is_satisfied(&[1, 3, 2, 4]));
+        assert!(!constraint.is_satisfied(&[1, 2, 3, 4]));
+        //the highest skyscraper hides the rest
+        assert!(!constraint.is_satisfied(&[2, 4, EMPTY, EMPTY]));
+        assert!(constraint.is_satisfied(&[2, 3, 4, EMPTY]));
+        //undecided positions might add visible skyscrapers
+        assert!(constraint.is_satisfied(&[1, EMPTY, EMPTY, EMPTY]));
+        assert!(constraint.is_satisfied(&[1, 2, EMPTY, 3]));
+        assert!(!constraint.is_satisfied(&[1, 2, 3, EMPTY]));
+    }
+
+    #[test]
+    fn check_straight() {
+        let constraint = Constraint::new_straight(vec![1, 2, 3]);
+        assert_eq!(constraint.relation, 's');
+
+        assert!(constraint.is_satisfied(&[4, 2, 3]));
+        assert!(!constraint.is_satisfied(&[4, 2, 1]));
+        assert!(constraint.is_satisfied(&[EMPTY, EMPTY, EMPTY]));
+        assert!(constraint.is_satisfied(&[5, EMPTY, 3]));
+        assert!(!constraint.is_satisfied(&[6, EMPTY, 3]));
+    }
+
+    #[test]
+    fn check_equality() {
+        let constraint = Constraint::new_equality(66, 100);
+        assert_eq!(constraint.positions, vec!(66, 100));
+        assert!(constraint.is_satisfied(&[3, 3]));
+        assert!(constraint.is_satisfied(&[3, EMPTY]));
+        assert!(!constraint.is_satisfied(&[3, 4]));
+    }
+
+    #[test]
+    fn check_parity() {
+        let even = Constraint::new_parity(12, 0);
+        assert_eq!(even.positions, vec!(12));
+        assert!(even.is_satisfied(&[4]));
+        assert!(even.is_satisfied(&[0]));
+        assert!(even.is_satisfied(&[EMPTY]));
+        assert!(!even.is_satisfied(&[5]));
+        let odd = Constraint::new_parity(12, 1);
+        assert!(odd.is_satisfied(&[5]));
+        assert!(!odd.is_satisfied(&[4]));
+    }
+
+    #[test]
+    fn check_chess_moves() {
+        //knight's moves on a 3x3 grid form a cycle of the outer positions, the center is not reachable
+        let constraints = Constraint::new_chess_moves(3, 0, &KNIGHT_MOVES);
+        assert_eq!(constraints.len(), 8);
+        assert!(constraints.iter().all(|constraint| constraint.relation == '!'));
+        assert!(constraints.contains(&Constraint {
+            relation: '!',
+            result: 0,
+            positions: vec!(0, 12),
+            crusts: (0, 0)
+        }));
+        assert!(!constraints.iter().any(|constraint| constraint.positions.contains(&11)));
+
+        //king's moves in the second grid of a multi grid Sudoku
+        let constraints = Constraint::new_chess_moves(2, 100, &KING_MOVES);
+        assert_eq!(constraints.len(), 6);
+        assert!(constraints[0].is_satisfied(&[1, 2]));
+        assert!(!constraints[0].is_satisfied(&[2, 2]));
+
+        let mut constraint_list = ConstraintList::new();
+        constraints.into_iter().for_each(|constraint| constraint_list.add(constraint));
+        let mut field = vec![EMPTY; 200];
+        field[100] = 1;
+        field[111] = 1;
+        let violations = constraint_list.get_violations(&field);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].positions, vec!(100, 111));
+    }
+
+    #[test]
+    fn check_filter_options() {
+        let mut constraint_list = ConstraintList::new();
+        assert!(constraint_list.is_empty());
+        constraint_list.add(Constraint::new_inequality(4, "00<01").unwrap());
+        constraint_list.add(Constraint::new_inequality(4, "02>12").unwrap());
+
+        let mut field = vec![EMPTY; 90];
+        let options = vec![vec![1, 2, 3], vec![2, 1, 3], vec![3, 2, 1]];
+
+        //only the constraint within the group can be checked
+        let valid_options = constraint_list.filter_options(&[0, 1, 2], options.clone(), &field);
+        assert_eq!(valid_options, vec!(vec!(1, 2, 3)));
+
+        //with position 12 set, the constraint to position 2 is checked as well
+        let options_1_2_0 = vec![vec![3, 2, 1], vec![3, 1, 2]];
+        let valid_options = constraint_list.filter_options(&[1, 2, 0], options_1_2_0.clone(), &field);
+        assert_eq!(valid_options, options_1_2_0);
+        field[12] = 1;
+        let valid_options = constraint_list.filter_options(&[1, 2, 0], options_1_2_0, &field);
+        assert_eq!(valid_options, vec!(vec!(3, 2, 1)));
+
+        //groups without constraints keep all options
+        let valid_options = constraint_list.filter_options(&[30, 31], options.clone(), &field);
+        assert_eq!(valid_options, options);
+    }
+}
@@ -0,0 +1,1084 @@
+//! The module kk_diagnostic is part of kenken_solve
+//! and provides a validating pass over the text of a puzzle file
+//!
+//! Instead of stopping at the first problem, all problems of the file are collected
+//! as diagnostics with the line and column of the offending token and a suggestion,
+//! e.g. a misspelled game type, an unknown operation in a KenKen cage, an invalid
+//! character in a Sudoku row or a Skyscrapers clue, or a constraint of a section
+//! which can't be parsed.
+//!
+
+use std::fmt;
+
+use crate::kk_constraint::{Constraint, ConstraintParser};
+use crate::kk_load::{get_header, is_connected, GAME_TYPES, RULES, SECTIONS};
+
+/// A problem found in the text of a puzzle file
+#[derive(Debug, Clone, PartialEq, Getters)]
+pub struct Diagnostic {
+    /// line of the file, starting with 1
+    line: usize,
+    /// column of the token within the line, starting with 1
+    column: usize,
+    token: String,
+    message: String,
+    suggestion: String,
+}
+
+impl Diagnostic {
+    fn new(line: usize, column: usize, token: &str, message: &str, suggestion: &str) -> Self {
+        Diagnostic {
+            line,
+            column,
+            token: token.to_string(),
+            message: message.to_string(),
+            suggestion: suggestion.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {} '{}' - {}",
+            self.line, self.column, self.message, self.token, self.suggestion
+        )
+    }
+}
+
+/// A non empty line of the file with its line number and the column of its first character
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    number: usize,
+    column: usize,
+    text: &'a str,
+}
+
+/// Checks the text of a puzzle file and returns all problems found, an empty list
+/// if the file passes the checks. Only the text format is checked, i.e. the description,
+/// the headers, the game type with its rules, the section names, the lines of the puzzle
+/// for each game type and the lines of the sections, the rules of the puzzles
+/// (e.g. the cages of a KenKen as a whole) are checked when loading.
+pub fn get_diagnostics(raw_puzzle_string: &str) -> Vec<Diagnostic> {
+    let mut lines: Vec<Line> = raw_puzzle_string
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| Line {
+            number: index + 1,
+            column: line.chars().take_while(|c| c.is_whitespace()).count() + 1,
+            text: line.trim(),
+        })
+        .filter(|line| !line.text.is_empty())
+        .collect();
+    //the lines are taken from the end of the reversed list up to the specification of the puzzle
+    lines.reverse();
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    //first line is the description, followed by optional headers and the game type
+    if lines.pop().is_none() {
+        diagnostics.push(Diagnostic::new(
+            1,
+            1,
+            "",
+            "Empty puzzle file",
+            "start the file with a description followed by the game type, e.g. KenKen",
+        ));
+        return diagnostics;
+    }
+    let mut header_rules: Vec<(&Line, usize, &str)> = Vec::new();
+    let mut header_lines: Vec<Line> = Vec::new();
+    while lines
+        .last()
+        .is_some_and(|line| get_header(line.text).is_some())
+    {
+        header_lines.extend(lines.pop());
+    }
+    for line in header_lines.iter() {
+        if let Some((key, value)) = line.text.split_once(':') {
+            if key.trim().eq_ignore_ascii_case("rules") {
+                let value_offset = key.chars().count() + 1;
+                header_rules.extend(
+                    get_tokens(value).map(|(offset, rule)| (line, value_offset + offset, rule)),
+                );
+            }
+        }
+    }
+
+    let type_line = match lines.pop() {
+        Some(type_line) => type_line,
+        None => {
+            diagnostics.push(Diagnostic::new(
+                header_lines.last().map_or(1, |line| line.number) + 1,
+                1,
+                "",
+                "Missing game type",
+                &format!("add a line with one of {}", get_names(&GAME_TYPES)),
+            ));
+            return diagnostics;
+        }
+    };
+    let mut type_tokens = get_tokens(type_line.text);
+    let (_, type_name) = type_tokens.next().unwrap_or((0, ""));
+    let game_type = GAME_TYPES
+        .iter()
+        .find(|(name, _)| *name == type_name)
+        .map(|&(name, _)| name);
+    if game_type.is_none() {
+        diagnostics.push(Diagnostic::new(
+            type_line.number,
+            type_line.column,
+            type_name,
+            "Unknown game type",
+            &get_suggestion(type_name, &get_names(&GAME_TYPES)),
+        ));
+    }
+    let type_rules = type_tokens.map(|(offset, rule)| (&type_line, offset, rule));
+    for (line, offset, rule_name) in type_rules.chain(header_rules) {
+        if !RULES.iter().any(|(name, _)| *name == rule_name) {
+            diagnostics.push(Diagnostic::new(
+                line.number,
+                line.column + offset,
+                rule_name,
+                "Unknown rule",
+                &get_suggestion(rule_name, &get_names(&RULES)),
+            ));
+        }
+    }
+
+    //the specification of the puzzle up to the first section
+    lines.reverse();
+    let section_start = lines.iter().position(|line| is_section_line(line.text));
+    let sections = lines.split_off(section_start.unwrap_or(lines.len()));
+    for line in sections.iter().filter(|line| is_section_line(line.text)) {
+        let name = line.text[1..line.text.len() - 1].trim();
+        if !SECTIONS.contains(&name) {
+            diagnostics.push(Diagnostic::new(
+                line.number,
+                line.column,
+                line.text,
+                "Unknown section",
+                &get_suggestion(name, &SECTIONS.join(", ")),
+            ));
+        }
+    }
+
+    let dimension = get_dimension(game_type, &lines, &sections);
+    let symbols = get_symbols(&sections, dimension);
+    match game_type {
+        Some("KenKen") if get_section_lines(&sections, "Cages").is_some() => check_cage_map(
+            &lines,
+            &get_section_lines(&sections, "Cages").unwrap_or_default(),
+            &mut diagnostics,
+        ),
+        Some("KenKen") => lines
+            .iter()
+            .for_each(|line| check_cage_line(line, &mut diagnostics)),
+        Some("Sudoku" | "Futoshiki" | "Str8ts") => {
+            check_rows(&lines, dimension, &symbols, &mut diagnostics)
+        }
+        Some("Skyscrapers") => check_skyscrapers_clues(&lines, dimension, &mut diagnostics),
+        Some("Kakuro") => check_map(&lines, dimension, &['#', '-'], &mut diagnostics),
+        Some("InshiNoHeya") => check_rooms(&lines, dimension, &mut diagnostics),
+        Some("MultiSudoku") => check_multi_sudoku_rows(&lines, &symbols, &mut diagnostics),
+        _ => (),
+    }
+
+    //sections required by the game type
+    let required_section = match game_type {
+        Some("Kakuro") => Some("Clues"),
+        Some("Str8ts") => Some("Black"),
+        Some("MultiSudoku") => Some("Grids"),
+        _ => None,
+    };
+    if let Some(name) = required_section.filter(|&name| get_section_lines(&sections, name).is_none()) {
+        diagnostics.push(Diagnostic::new(
+            type_line.number,
+            type_line.column,
+            type_name,
+            &format!("Missing section [{}]", name),
+            &format!("add the section [{}] after the lines of the puzzle", name),
+        ));
+    }
+    check_sections(game_type, &lines, &sections, dimension, &symbols, &mut diagnostics);
+
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    diagnostics
+}
+
+/// Splits a text into its tokens separated by whitespace with the offset of each token
+fn get_tokens(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    text.split(' ').filter_map(move |token| {
+        let token_offset = offset;
+        offset += token.chars().count() + 1;
+        match token.trim() {
+            "" => None,
+            token => Some((token_offset, token)),
+        }
+    })
+}
+
+fn get_names<T>(named_values: &[(&str, T)]) -> String {
+    named_values
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+fn is_section_line(text: &str) -> bool {
+    text.starts_with('[') && text.ends_with(']')
+}
+
+/// Retrieves the lines of the section with the given name
+fn get_section_lines<'a>(sections: &'a [Line<'a>], name: &str) -> Option<Vec<&'a Line<'a>>> {
+    let start = sections.iter().position(|line| {
+        is_section_line(line.text) && line.text[1..line.text.len() - 1].trim() == name
+    })?;
+    Some(
+        sections[start + 1..]
+            .iter()
+            .take_while(|line| !is_section_line(line.text))
+            .collect(),
+    )
+}
+
+/// Retrieves the dimension of the puzzle from the lines of the puzzle, i.e. 9 for Sudokus,
+/// one clue per row for Skyscrapers, the largest row or column of the cages of a KenKen
+/// and one line per row for all other puzzles
+fn get_dimension(game_type: Option<&str>, lines: &[Line], sections: &[Line]) -> usize {
+    match game_type {
+        Some("Sudoku" | "MultiSudoku") => 9,
+        Some("Skyscrapers") => lines.first().map_or(0, |line| line.text.chars().count()),
+        Some("KenKen") if get_section_lines(sections, "Cages").is_none() => lines
+            .iter()
+            .flat_map(|line| {
+                line.text
+                    .trim_start_matches(|c: char| c == '!' || c.is_ascii_digit())
+                    .chars()
+                    .skip(1)
+                    .filter_map(|c| c.to_digit(10))
+            })
+            .max()
+            .map_or(0, |digit| digit as usize + 1),
+        _ => lines.len(),
+    }
+}
+
+/// Retrieves the symbols of the digits, i.e. the section "Symbols", the section "Digits"
+/// or the digits 1 to dimension
+fn get_symbols(sections: &[Line], dimension: usize) -> Vec<char> {
+    for name in ["Symbols", "Digits"] {
+        if let Some(symbol_line) =
+            get_section_lines(sections, name).and_then(|lines| lines.first().copied())
+        {
+            return symbol_line.text.chars().collect();
+        }
+    }
+    (1..=dimension.min(9))
+        .filter_map(|digit| char::from_digit(digit as u32, 10))
+        .collect()
+}
+
+/// Proposes the name closest to the given one, i.e. the name starting with it (ignoring the case)
+/// or with the least count of edited characters, otherwise lists all names
+fn get_suggestion(token: &str, names: &str) -> String {
+    let token_lowercase = token.to_lowercase();
+    let starting_with = names
+        .split(", ")
+        .find(|name| name.to_lowercase().starts_with(&token_lowercase));
+    if let Some(name) = starting_with.filter(|_| !token.is_empty()) {
+        return format!("did you mean '{}'?", name);
+    }
+    let closest = names
+        .split(", ")
+        .map(|name| {
+            (
+                get_edit_distance(&token_lowercase, &name.to_lowercase()),
+                name,
+            )
+        })
+        .min();
+    match closest {
+        Some((distance, name)) if distance <= 2.max(name.len() / 3) => {
+            format!("did you mean '{}'?", name)
+        }
+        _ => format!("use one of {}", names),
+    }
+}
+
+/// Counts the characters to insert, delete or replace to change one text into the other
+fn get_edit_distance(text: &str, other_text: &str) -> usize {
+    let other_chars: Vec<char> = other_text.chars().collect();
+    let mut distances: Vec<usize> = (0..=other_chars.len()).collect();
+    for (index, c) in text.chars().enumerate() {
+        let mut previous_diagonal = distances[0];
+        distances[0] = index + 1;
+        for (other_index, &other_c) in other_chars.iter().enumerate() {
+            let diagonal = previous_diagonal + usize::from(c != other_c);
+            previous_diagonal = distances[other_index + 1];
+            distances[other_index + 1] = diagonal
+                .min(distances[other_index] + 1)
+                .min(previous_diagonal + 1);
+        }
+    }
+    distances[other_chars.len()]
+}
+
+/// Checks a cage line "[!][result][operation][position].[position]...", e.g. "12+00.01.11"
+fn check_cage_line(line: &Line, diagnostics: &mut Vec<Diagnostic>) {
+    let chars: Vec<char> = line.text.chars().collect();
+    let Some(positions_start) = check_cage_clue(line, &chars, diagnostics) else {
+        return;
+    };
+    if positions_start == chars.len() {
+        diagnostics.push(Diagnostic::new(
+            line.number,
+            line.column + positions_start,
+            line.text,
+            "Cage without positions",
+            "add the positions of the cage after the operation, e.g. 12+00.01",
+        ));
+        return;
+    }
+
+    let mut offset = positions_start;
+    for position in chars[positions_start..].split(|&c| c == '.') {
+        let is_position = position.len() == 2 && position.iter().all(|c| ('0'..='8').contains(c));
+        if !is_position {
+            diagnostics.push(Diagnostic::new(
+                line.number,
+                line.column + offset,
+                &position.iter().collect::<String>(),
+                "No valid position",
+                "a position has two digits, the row and the column from 0 to 8, e.g. 01",
+            ));
+        }
+        offset += position.len() + 1;
+    }
+}
+
+/// Checks the clue of a cage "[!][result][operation]" at the start of the given characters
+/// and returns the index after the operation, none if the clue isn't valid
+fn check_cage_clue(
+    line: &Line,
+    chars: &[char],
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<usize> {
+    let result_start = usize::from(chars.first() == Some(&'!'));
+    let operation_index = result_start
+        + chars[result_start..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+    if operation_index == result_start {
+        let token: String = chars[result_start..]
+            .iter()
+            .take_while(|&&c| c != '.')
+            .collect();
+        diagnostics.push(Diagnostic::new(
+            line.number,
+            line.column + result_start,
+            &token,
+            "Cage without result",
+            "start the cage with its result followed by the operation, e.g. 12+",
+        ));
+        return None;
+    }
+    match chars.get(operation_index) {
+        Some('+' | '-' | '*' | ':' | 'c') => Some(operation_index + 1),
+        operation => {
+            let token: String = operation.map_or(String::new(), |c| c.to_string());
+            diagnostics.push(Diagnostic::new(
+                line.number,
+                line.column + operation_index,
+                &token,
+                "No valid operation",
+                "use one of '+', '-', '*', ':' or 'c' after the result",
+            ));
+            None
+        }
+    }
+}
+
+/// Checks the rows of a cage map and the clues of the cages in the section "Cages", e.g. "A 12+"
+fn check_cage_map(rows: &[Line], cage_clues: &[&Line], diagnostics: &mut Vec<Diagnostic>) {
+    check_rows(rows, rows.len(), &[], diagnostics);
+    for line in cage_clues {
+        let tokens: Vec<(usize, &str)> = get_tokens(line.text).collect();
+        match tokens[..] {
+            [(_, id), (clue_offset, clue)] if id.chars().count() == 1 => {
+                let clue_line = Line {
+                    number: line.number,
+                    column: line.column + clue_offset,
+                    text: clue,
+                };
+                let chars: Vec<char> = clue.chars().collect();
+                let clue_end = check_cage_clue(&clue_line, &chars, diagnostics);
+                if clue_end.is_some_and(|end| end < chars.len()) {
+                    diagnostics.push(Diagnostic::new(
+                        line.number,
+                        clue_line.column + clue_end.unwrap_or_default(),
+                        clue,
+                        "Unexpected characters after the operation",
+                        "the positions of the cage are given by the cage map",
+                    ));
+                }
+            }
+            _ => diagnostics.push(Diagnostic::new(
+                line.number,
+                line.column,
+                line.text,
+                "No valid cage clue",
+                "give the character of the cage in the map and its clue, e.g. A 12+",
+            )),
+        }
+    }
+}
+
+/// Checks the count of rows and the positions of each row, '.' might separate positions.
+/// Unless the symbols are empty each position is either '-' or one of the symbols.
+fn check_rows(
+    rows: &[Line],
+    dimension: usize,
+    symbols: &[char],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if rows.is_empty() {
+        return;
+    }
+    if rows.len() != dimension || !(3..=9).contains(&dimension) {
+        let last_row = &rows[rows.len() - 1];
+        diagnostics.push(Diagnostic::new(
+            last_row.number,
+            last_row.column,
+            last_row.text,
+            &format!("Found {} rows", rows.len()),
+            &format!(
+                "give {} rows with one position per column each",
+                dimension.clamp(3, 9)
+            ),
+        ));
+    }
+
+    for row in rows {
+        let mut positions = 0;
+        for (offset, c) in row.text.chars().enumerate().filter(|&(_, c)| c != '.') {
+            positions += 1;
+            if !symbols.is_empty() && c != '-' && !symbols.contains(&c) {
+                let symbol_list: String = symbols.iter().collect();
+                diagnostics.push(Diagnostic::new(
+                    row.number,
+                    row.column + offset,
+                    &c.to_string(),
+                    "No valid digit",
+                    &format!("use one of {} or '-' for an open position", symbol_list),
+                ));
+            }
+        }
+        if positions != dimension {
+            diagnostics.push(Diagnostic::new(
+                row.number,
+                row.column,
+                row.text,
+                &format!("Row has {} positions instead of {}", positions, dimension),
+                &format!("give one position per column, i.e. {} per row", dimension),
+            ));
+        }
+    }
+}
+
+/// Checks the 4 lines of Skyscrapers clues (top, bottom, left, right),
+/// each with one clue from 1 to dimension or '-' per column or row
+fn check_skyscrapers_clues(lines: &[Line], dimension: usize, diagnostics: &mut Vec<Diagnostic>) {
+    if lines.len() != 4 || !(3..=9).contains(&dimension) {
+        if let Some(last_line) = lines.last() {
+            diagnostics.push(Diagnostic::new(
+                last_line.number,
+                last_line.column,
+                last_line.text,
+                &format!("Found {} lines of clues with {} clues", lines.len(), dimension),
+                "give 4 lines of clues (top, bottom, left, right) with 3 to 9 clues each",
+            ));
+        }
+        return;
+    }
+    let clues: Vec<char> = (1..=dimension)
+        .filter_map(|clue| char::from_digit(clue as u32, 10))
+        .collect();
+    for line in lines {
+        check_marks(line, &clues, diagnostics);
+        if line.text.chars().count() != dimension {
+            diagnostics.push(Diagnostic::new(
+                line.number,
+                line.column,
+                line.text,
+                &format!("Line has {} clues instead of {}", line.text.chars().count(), dimension),
+                "give one clue (or '-') per row or column",
+            ));
+        }
+    }
+}
+
+/// Checks a map with one line per row and one character per position,
+/// each character must be one of the given marks
+fn check_map(rows: &[Line], dimension: usize, marks: &[char], diagnostics: &mut Vec<Diagnostic>) {
+    if rows.is_empty() {
+        return;
+    }
+    if rows.len() != dimension || !(3..=9).contains(&dimension) {
+        let last_row = &rows[rows.len() - 1];
+        diagnostics.push(Diagnostic::new(
+            last_row.number,
+            last_row.column,
+            last_row.text,
+            &format!("Found {} rows", rows.len()),
+            &format!(
+                "give {} rows with one position per column each",
+                dimension.clamp(3, 9)
+            ),
+        ));
+    }
+    for row in rows {
+        check_marks(row, marks, diagnostics);
+        let positions = row.text.chars().count();
+        if positions != dimension {
+            diagnostics.push(Diagnostic::new(
+                row.number,
+                row.column,
+                row.text,
+                &format!("Row has {} positions instead of {}", positions, dimension),
+                &format!("give one position per column, i.e. {} per row", dimension),
+            ));
+        }
+    }
+}
+
+/// Reports each character of the line other than the given marks and '-'
+fn check_marks(line: &Line, marks: &[char], diagnostics: &mut Vec<Diagnostic>) {
+    for (offset, c) in line.text.chars().enumerate() {
+        if c != '-' && !marks.contains(&c) {
+            let mark_list: String = marks.iter().filter(|&&mark| mark != '-').collect();
+            diagnostics.push(Diagnostic::new(
+                line.number,
+                line.column + offset,
+                &c.to_string(),
+                "No valid character",
+                &format!("use one of {} or '-'", mark_list),
+            ));
+        }
+    }
+}
+
+/// Checks the rooms of an Inshi no heya, each row holds rooms "[product]([length])"
+/// covering all positions of the row, e.g. "12(2) 3(1)"
+fn check_rooms(rows: &[Line], dimension: usize, diagnostics: &mut Vec<Diagnostic>) {
+    if !(3..=9).contains(&dimension) {
+        check_map(rows, dimension, &[], diagnostics);
+        return;
+    }
+    for row in rows {
+        let mut length_sum = Some(0);
+        for (offset, room) in get_tokens(row.text) {
+            let length = room
+                .strip_suffix(')')
+                .and_then(|room| room.split_once('('))
+                .filter(|(product, _)| product.parse::<usize>().is_ok())
+                .and_then(|(_, length)| length.parse::<usize>().ok())
+                .filter(|&length| length > 0);
+            match length {
+                Some(length) => length_sum = length_sum.map(|sum| sum + length),
+                None => {
+                    length_sum = None;
+                    diagnostics.push(Diagnostic::new(
+                        row.number,
+                        row.column + offset,
+                        room,
+                        "No valid room",
+                        "give the product and the length of the room, e.g. 12(2)",
+                    ));
+                }
+            }
+        }
+        if let Some(length_sum) = length_sum.filter(|&sum| sum != dimension) {
+            diagnostics.push(Diagnostic::new(
+                row.number,
+                row.column,
+                row.text,
+                &format!("Rooms cover {} positions instead of {}", length_sum, dimension),
+                &format!("the rooms of a row must cover its {} positions", dimension),
+            ));
+        }
+    }
+}
+
+/// Checks the rows of a multi grid Sudoku, each position is '-', a digit or '#'
+/// for a position outside of the grids
+fn check_multi_sudoku_rows(rows: &[Line], symbols: &[char], diagnostics: &mut Vec<Diagnostic>) {
+    let marks: Vec<char> = symbols.iter().copied().chain(['#']).collect();
+    for row in rows {
+        check_marks(row, &marks, diagnostics);
+    }
+}
+
+/// Checks the grid offsets "[row] [column]" of a multi grid Sudoku, each grid
+/// must lie within the rows of the puzzle
+fn check_grids(rows: &[Line], grid_lines: &[Line], diagnostics: &mut Vec<Diagnostic>) {
+    let rows: Vec<Vec<char>> = rows.iter().map(|row| row.text.chars().collect()).collect();
+    for line in grid_lines {
+        let offsets: Vec<Option<usize>> = line
+            .text
+            .split_whitespace()
+            .map(|offset| offset.parse::<usize>().ok())
+            .collect();
+        let (row_offset, column_offset) = match offsets[..] {
+            //an offset beyond the rows can't hold a grid, nor overflow with its 9 positions
+            [Some(row_offset), Some(column_offset)]
+                if row_offset < rows.len() && column_offset < rows[row_offset].len() =>
+            {
+                (row_offset, column_offset)
+            }
+            _ => {
+                diagnostics.push(Diagnostic::new(
+                    line.number,
+                    line.column,
+                    line.text,
+                    "No valid grid offset",
+                    "give the row and the column of the upper left corner of the grid, e.g. 6 6",
+                ));
+                continue;
+            }
+        };
+        let within_rows = (row_offset..row_offset + 9).all(|row| {
+            (column_offset..column_offset + 9).all(|column| {
+                rows.get(row)
+                    .and_then(|r| r.get(column))
+                    .is_some_and(|&c| c != '#')
+            })
+        });
+        if !within_rows {
+            diagnostics.push(Diagnostic::new(
+                line.number,
+                line.column,
+                line.text,
+                "Grid exceeds the rows of the puzzle",
+                "each position of the 9x9 grid must be a digit or '-' in the rows of the puzzle",
+            ));
+        }
+    }
+}
+
+/// Checks a Kakuro clue "[sum][direction][position]", the direction is '>' or 'v'
+/// and the position is a black position of the map
+fn check_kakuro_clue(rows: &[Line], line: &Line, diagnostics: &mut Vec<Diagnostic>) {
+    let clue = line
+        .text
+        .split_once(['>', 'v'])
+        .filter(|(sum, _)| sum.trim().parse::<usize>().is_ok())
+        .and_then(|(_, position)| position.trim().parse::<usize>().ok());
+    match clue {
+        Some(position)
+            if rows
+                .get(position / 10)
+                .and_then(|row| row.text.chars().nth(position % 10))
+                == Some('#') => {}
+        Some(_) => diagnostics.push(Diagnostic::new(
+            line.number,
+            line.column,
+            line.text,
+            "Clue at no black position",
+            "a clue belongs to a black position '#' of the map",
+        )),
+        None => diagnostics.push(Diagnostic::new(
+            line.number,
+            line.column,
+            line.text,
+            "No valid Kakuro clue",
+            "give the sum, the direction '>' or 'v' and the position of the clue, e.g. 12v01",
+        )),
+    }
+}
+
+/// Checks a region given by its positions separated by '.', e.g. "00.11.22",
+/// the positions are distinct and within the grid
+fn check_positions(line: &Line, dimension: usize, diagnostics: &mut Vec<Diagnostic>) {
+    let mut positions: Vec<&str> = Vec::new();
+    let mut offset = 0;
+    for position in line.text.split('.') {
+        let is_position = position.len() == 2
+            && position
+                .chars()
+                .all(|c| c.to_digit(10).is_some_and(|digit| (digit as usize) < dimension));
+        if !is_position || positions.contains(&position) {
+            diagnostics.push(Diagnostic::new(
+                line.number,
+                line.column + offset,
+                position,
+                "No valid position",
+                &format!(
+                    "a position has two digits, the row and the column from 0 to {}, and is given once",
+                    dimension - 1
+                ),
+            ));
+        }
+        positions.push(position);
+        offset += position.chars().count() + 1;
+    }
+    if positions.len() > dimension {
+        diagnostics.push(Diagnostic::new(
+            line.number,
+            line.column,
+            line.text,
+            &format!("Region has {} positions", positions.len()),
+            &format!("a region has at most {} positions", dimension),
+        ));
+    }
+}
+
+/// Checks a region map of a Sudoku, 9 rows with 9 positions each naming the region
+/// of the position, which define 9 connected regions of 9 positions each
+fn check_region_map(rows: &[Line], diagnostics: &mut Vec<Diagnostic>) {
+    let count = diagnostics.len();
+    check_rows(rows, 9, &[], diagnostics);
+    if diagnostics.len() > count {
+        return;
+    }
+
+    //the positions of each region in order of appearance with the line and column of its first position
+    let mut regions: Vec<(char, Vec<usize>, usize, usize)> = Vec::new();
+    for (row_index, row) in rows.iter().enumerate() {
+        let ids = row.text.chars().enumerate().filter(|&(_, c)| c != '.');
+        for (column, (offset, id)) in ids.enumerate() {
+            let position = row_index * 10 + column;
+            match regions.iter_mut().find(|(region_id, ..)| *region_id == id) {
+                Some((_, positions, ..)) => positions.push(position),
+                None => regions.push((id, vec![position], row.number, row.column + offset)),
+            }
+        }
+    }
+    for (id, positions, line, column) in regions.iter() {
+        let problem = match positions.len() {
+            9 if is_connected(positions) => continue,
+            9 => "Region is not connected".to_string(),
+            count => format!("Region has {} positions", count),
+        };
+        diagnostics.push(Diagnostic::new(
+            *line,
+            *column,
+            &id.to_string(),
+            &problem,
+            "a region has 9 orthogonally connected positions",
+        ));
+    }
+    if regions.len() != 9 {
+        diagnostics.push(Diagnostic::new(
+            rows[8].number,
+            rows[8].column,
+            rows[8].text,
+            &format!("Found {} regions", regions.len()),
+            "the region map must define 9 regions",
+        ));
+    }
+}
+
+/// Checks the line of the section "Digits" or "Symbols", i.e. one distinct digit (or symbol)
+/// per digit of the puzzle
+fn check_digit_line(line: &Line, name: &str, digit_count: usize, diagnostics: &mut Vec<Diagnostic>) {
+    let mut found: Vec<char> = Vec::new();
+    let (is_valid, suggestion): (fn(char) -> bool, &str) = match name {
+        "Digits" => (|c| c.is_ascii_digit(), "give distinct digits from 0 to 9"),
+        _ => (|c| c != '-' && c != '.', "give distinct symbols other than '-' and '.'"),
+    };
+    for (offset, c) in line.text.chars().enumerate() {
+        if !is_valid(c) || found.contains(&c) {
+            diagnostics.push(Diagnostic::new(
+                line.number,
+                line.column + offset,
+                &c.to_string(),
+                &format!("No valid character of the section [{}]", name),
+                suggestion,
+            ));
+        }
+        found.push(c);
+    }
+    if found.len() != digit_count {
+        diagnostics.push(Diagnostic::new(
+            line.number,
+            line.column,
+            line.text,
+            &format!("Found {} characters", found.len()),
+            &format!("give one character per digit, i.e. {}", digit_count),
+        ));
+    }
+}
+
+/// Checks the lines of all sections, constraints are checked by parsing them
+fn check_sections(
+    game_type: Option<&str>,
+    lines: &[Line],
+    sections: &[Line],
+    dimension: usize,
+    symbols: &[char],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    //the positions of all sections are within the dimension, a sandwich clue
+    //is parsed with the digits 1 to dimension, as its format doesn't depend on the digits
+    if !(3..=9).contains(&dimension) {
+        return;
+    }
+    let digits: Vec<usize> = (1..=dimension).collect();
+    let constraint_sections: [(&str, ConstraintParser, &str); 5] = [
+        ("Inequalities", &|line| Constraint::new_inequality(dimension, line), "e.g. 00<01"),
+        ("Adjacent", &|line| Constraint::new_adjacent(dimension, line), "e.g. 00w01"),
+        ("Sandwiches", &|line| Constraint::new_sandwich(&digits, line), "e.g. r0=15"),
+        ("Thermometers", &|line| Constraint::new_thermometer(dimension, line), "e.g. 00.01.12"),
+        ("Arrows", &|line| Constraint::new_arrow(dimension, line), "e.g. 00.01.12"),
+    ];
+    for (name, new_constraint, example) in constraint_sections {
+        for line in get_section_lines(sections, name).unwrap_or_default() {
+            if let Err(message) = new_constraint(line.text) {
+                //the message of the parser ends with the line, which is the token of the diagnostic
+                let suffix = format!(": {}", line.text);
+                diagnostics.push(Diagnostic::new(
+                    line.number,
+                    line.column,
+                    line.text,
+                    message.strip_suffix(&suffix).unwrap_or(&message),
+                    &format!("give one constraint per line, {}", example),
+                ));
+            }
+        }
+    }
+
+    let section_lines = |name: &str| -> Vec<Line> {
+        get_section_lines(sections, name)
+            .unwrap_or_default()
+            .into_iter()
+            .copied()
+            .collect()
+    };
+    check_map(&section_lines("Parity"), dimension, &['e', 'o'], diagnostics);
+    check_map(&section_lines("Black"), dimension, &['#'], diagnostics);
+    check_rows(&section_lines("Givens"), dimension, symbols, diagnostics);
+    if get_section_lines(sections, "Regions").is_some() {
+        check_region_map(&section_lines("Regions"), diagnostics);
+    }
+    for line in section_lines("Extra Regions") {
+        check_positions(&line, dimension, diagnostics);
+    }
+    if get_section_lines(sections, "Grids").is_some() {
+        check_grids(lines, &section_lines("Grids"), diagnostics);
+    }
+    if game_type == Some("Kakuro") {
+        for line in section_lines("Clues") {
+            check_kakuro_clue(lines, &line, diagnostics);
+        }
+    }
+
+    //a Kakuro has the 9 digits of a Sudoku, the symbols are one per digit
+    let digit_count = match game_type {
+        Some("Kakuro") => 9,
+        _ => dimension,
+    };
+    for name in ["Digits", "Symbols"] {
+        if let Some(line) = section_lines(name).first() {
+            check_digit_line(line, name, digit_count, diagnostics);
+        }
+    }
+}
+
+#[cfg(test)]
+mod kk_diagnostic_tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn check_valid_files() {
+        for file_name in [
+            "KK-Dim4-1.txt",
+            "KK-Dim9-1.txt",
+            "KK-Map-1.txt",
+            "S-1.txt",
+            "S-Jigsaw-1.txt",
+            "S-Hyper-1.txt",
+            "F-1.txt",
+            "SK-1.txt",
+            "KA-1.txt",
+            "ST-1.txt",
+            "IN-1.txt",
+            "MS-Samurai-1.txt",
+            "MS-Twin-1.txt",
+            "S-AntiKnight-1.txt",
+        ] {
+            let raw_puzzle_string = fs::read_to_string(file_name).unwrap();
+            assert_eq!(
+                get_diagnostics(&raw_puzzle_string),
+                Vec::new(),
+                "{}",
+                file_name
+            );
+        }
+    }
+
+    #[test]
+    fn check_diagnostics() {
+        let raw_puzzle_string = "KenKen with typos\n\nKenKen DistinctCage\n1-00.01\n  8x02.03.12\n\
+            6*10.11.2\n+13.23\n16*21.30.31\n6+22.32.39\n[Giv]\n";
+        let diagnostics = get_diagnostics(raw_puzzle_string);
+        let found: Vec<(usize, usize, &str)> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.line,
+                    diagnostic.column,
+                    diagnostic.token.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (3, 8, "DistinctCage"),
+                (5, 4, "x"),
+                (6, 9, "2"),
+                (7, 1, "+13"),
+                (9, 9, "39"),
+                (10, 1, "[Giv]"),
+            ]
+        );
+        assert_eq!(diagnostics[0].suggestion, "did you mean 'DistinctCages'?");
+        assert_eq!(diagnostics[5].suggestion, "did you mean 'Givens'?");
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "line 5, column 4: No valid operation 'x' - use one of '+', '-', '*', ':' or 'c' after the result"
+        );
+
+        let sudoku = "Sudoku with a typo\nSudoku\n-5-.--8.269\n--2.-43.---\n--9.---.---\n\
+            ---.--7.---\n---.---.9--\n4-5.3--.--9-\n---.--2.46-\n56-.---.---\n\
+            3-4.--8.--x\n";
+        let found: Vec<(usize, usize, String)> = get_diagnostics(sudoku)
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.line,
+                    diagnostic.column,
+                    diagnostic.message.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (8, 1, "Row has 10 positions instead of 9".to_string()),
+                (11, 11, "No valid digit".to_string()),
+            ]
+        );
+
+        assert_eq!(get_diagnostics("").len(), 1);
+        assert_eq!(get_diagnostics("only a description\nid: 1\n")[0].line, 3);
+        assert_eq!(
+            get_diagnostics("misspelled\nsudoku\n")[0].suggestion,
+            "did you mean 'Sudoku'?"
+        );
+        let cage_map = "Cage map\nKenKen\nAAB\nCAB\nCC\n[Cages]\nA 3x\nB 6+00\nC\n";
+        assert_eq!(get_diagnostics(cage_map).len(), 4);
+    }
+
+    //replaces a line of a puzzle file and returns the line, column and message of each diagnostic
+    fn get_found(file_name: &str, line: &str, new_line: &str) -> Vec<(usize, usize, String)> {
+        let raw_puzzle_string = fs::read_to_string(file_name)
+            .unwrap()
+            .replacen(line, new_line, 1);
+        get_diagnostics(&raw_puzzle_string)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.line, diagnostic.column, diagnostic.message))
+            .collect()
+    }
+
+    #[test]
+    fn check_diagnostics_of_all_game_types() {
+        assert_eq!(
+            get_found("SK-1.txt", "--24-", "--2x-"),
+            vec![(4, 4, "No valid character".to_string())]
+        );
+        assert_eq!(
+            get_found("SK-1.txt", "--24-", "--2-"),
+            vec![(4, 1, "Line has 4 clues instead of 5".to_string())]
+        );
+        assert_eq!(
+            get_found("KA-1.txt", "6>10\n", "6>11\n12x01\n"),
+            vec![
+                (11, 1, "Clue at no black position".to_string()),
+                (12, 1, "No valid Kakuro clue".to_string()),
+            ]
+        );
+        assert_eq!(
+            get_found("KA-1.txt", "[Clues]", "[Clue]"),
+            vec![
+                (2, 1, "Missing section [Clues]".to_string()),
+                (8, 1, "Unknown section".to_string()),
+            ]
+        );
+        assert_eq!(
+            get_found("ST-1.txt", "#--#--", "#-x#-"),
+            vec![
+                (10, 1, "Row has 5 positions instead of 6".to_string()),
+                (10, 3, "No valid character".to_string()),
+            ]
+        );
+        assert_eq!(
+            get_found("IN-1.txt", "3(1) 10(3) 6(1)", "3(1) 10(3x) 6(2)"),
+            vec![(5, 6, "No valid room".to_string())]
+        );
+        assert_eq!(
+            get_found("IN-1.txt", "24(3) 30(3)", "24(3) 30(2)"),
+            vec![(6, 1, "Rooms cover 5 positions instead of 6".to_string())]
+        );
+        assert_eq!(
+            get_found("MS-Twin-1.txt", "6 6", "6 7"),
+            vec![(20, 1, "Grid exceeds the rows of the puzzle".to_string())]
+        );
+        assert_eq!(
+            get_found("MS-Twin-1.txt", "6 6", "18446744073709551615 6"),
+            vec![(20, 1, "No valid grid offset".to_string())]
+        );
+        assert_eq!(
+            get_found("MS-Twin-1.txt", "6 6", "6 18446744073709551615"),
+            vec![(20, 1, "No valid grid offset".to_string())]
+        );
+        assert_eq!(
+            get_found("MS-Twin-1.txt", "--9--1---######", "--9--1--x######"),
+            vec![(3, 9, "No valid character".to_string())]
+        );
+        assert_eq!(
+            get_found("S-Jigsaw-1.txt", "111113333", "111113334"),
+            vec![
+                (13, 6, "Region has 8 positions".to_string()),
+                (13, 9, "Region has 10 positions".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_diagnostics_of_sections() {
+        let sections = "\n[Inequalities]\n00<01\n00<22\n[Parity]\neo-x\n----\n----\n----\n\
+            [Symbols]\nAB-\n[Extra Regions]\n00.11.44\n[Givens]\n-3--\n----\n----\n5---\n";
+        let found = get_found("KK-Dim4-1.txt", "6+22.32.33", &format!("6+22.32.33{}", sections));
+        assert_eq!(
+            found,
+            vec![
+                (11, 1, "Can't parse inequality or positions are no neighbours".to_string()),
+                (13, 4, "No valid character".to_string()),
+                (18, 1, "Found 3 characters".to_string()),
+                (18, 3, "No valid character of the section [Symbols]".to_string()),
+                (20, 7, "No valid position".to_string()),
+                (22, 2, "No valid digit".to_string()),
+                (25, 1, "No valid digit".to_string()),
+            ]
+        );
+        let digits = get_found("KA-1.txt", "4>41", "4>41\n[Digits]\n1234");
+        assert_eq!(digits, vec![(18, 1, "Found 4 characters".to_string())]);
+    }
+}
@@ -2,14 +2,18 @@ use rand::prelude::*;
 use rand::thread_rng;
 use rand::Rng;
 
-use crate::kk_load::PuzzleAsString;
-use crate::kk_puzzle::Puzzle;
+use crate::kk_load::GameType::{InshiNoHeya, KenKen};
+use crate::kk_load::{is_connected, GameType, PuzzleAsString};
+use crate::kk_puzzle::{Puzzle, EMPTY};
 
 #[derive(Debug, Clone)]
 pub struct GeneratedPuzzle {
+    game_type: GameType,
     dimension: usize,
+    digits: Vec<usize>,
     difficulty: usize,
     operations_range: usize,
+    distinct_digits: bool,
     solution: Vec<usize>,
     groups: Vec<Vec<usize>>,
     operations: Vec<char>,
@@ -22,7 +26,31 @@ impl GeneratedPuzzle {
     /// * dimension [3-9] - dimension of the generated KenKen
     /// * difficulty [1-4] - difficulty of the generated Kenken, influences the group sizes
     /// * operations_range [1,2] - only addition (0) or all operations (1) used in the generated KenKen
-    pub fn generate_kenken(dimension: usize, difficulty: usize, operations_range: usize) -> Self {
+    /// * distinct_digits - no digit is repeated within a group (rule DistinctCages)
+    pub fn generate_kenken(
+        dimension: usize,
+        difficulty: usize,
+        operations_range: usize,
+        distinct_digits: bool,
+    ) -> Self {
+        GeneratedPuzzle::generate_kenken_with_digits(
+            &(1..=dimension).collect::<Vec<usize>>(),
+            difficulty,
+            operations_range,
+            distinct_digits,
+        )
+    }
+
+    /// generates a new kenken with the given digits, e.g. 0 to 5 for a zero based 6x6 KenKen
+    /// Input:
+    /// * digits - digits (0-9) of the generated KenKen, the count of digits is the dimension [3-9]
+    /// * difficulty, operations_range and distinct_digits as for generate_kenken
+    pub fn generate_kenken_with_digits(
+        digits: &[usize],
+        difficulty: usize,
+        operations_range: usize,
+        distinct_digits: bool,
+    ) -> Self {
         //difficulty
         // 0 - easy    up to 9% 1x1fields - max 3-field groups
         // 1 - medium      up to 6% 1x1 fields
@@ -34,29 +62,77 @@ impl GeneratedPuzzle {
         // 1 - all operations +-*:
 
         let mut new_puzzle = GeneratedPuzzle {
-                dimension,
+                game_type: KenKen,
+                dimension: digits.len(),
+                digits: digits.to_vec(),
                 difficulty,
                 operations_range,
+                distinct_digits,
                 solution: Vec::new(),
                 groups: Vec::new(),
                 operations: Vec::new(),
                 results: Vec::new(),
             };
 
-        for _ in 0..10{
+        new_puzzle.add_unique_groups();
 
-            new_puzzle.add_groups();
-            new_puzzle.add_solution();
-            new_puzzle.add_operations();
+        new_puzzle
+    }
 
-            if new_puzzle.check_groups() { break }
+    /// generates a new Inshi no heya, i.e. a KenKen with horizontal multiplication rooms,
+    /// with a given dimension and difficulty
+    /// Input:
+    /// * dimension [3-9] - dimension of the generated Inshi no heya
+    /// * difficulty [0-3] - difficulty of the generated Inshi no heya, influences the room lengths
+    pub fn generate_inshi_no_heya(dimension: usize, difficulty: usize) -> Self {
+        let mut new_puzzle = GeneratedPuzzle {
+            game_type: InshiNoHeya,
+            dimension,
+            digits: (1..=dimension).collect(),
+            difficulty,
+            operations_range: 1,
+            distinct_digits: false,
+            solution: Vec::new(),
+            groups: Vec::new(),
+            operations: Vec::new(),
+            results: Vec::new(),
+        };
+
+        new_puzzle.add_unique_groups();
 
-            new_puzzle.groups.clear();
-            new_puzzle.operations.clear();
-            new_puzzle.results.clear();
+        new_puzzle
+    }
+
+    /// adds a solution with groups and operations,
+    /// up to 10 tries (100 for the less often unique Inshi no heya)
+    /// to get a puzzle with a unique solution
+    fn add_unique_groups(&mut self) {
+        let tries = match self.game_type {
+            InshiNoHeya => 100,
+            _ => 10,
+        };
+        for _ in 0..tries {
+
+            //the solution is needed first, to keep the digits of the groups distinct
+            self.add_solution();
+            match self.game_type {
+                InshiNoHeya => self.add_rooms(),
+                _ => self.add_groups(),
+            }
+            self.add_operations();
+
+            if self.check_groups() { break }
+
+            self.groups.clear();
+            self.operations.clear();
+            self.results.clear();
         }
+    }
 
-        new_puzzle
+    /// returns the generated puzzle as a JSON object in the format of JsonPuzzle,
+    /// i.e. the puzzle file of to_raw_string without the internals of the generator
+    pub fn to_json(&self) -> Result<String, String> {
+        PuzzleAsString::new_from_raw_string(self.to_raw_string(), true)?.to_json()
     }
 
     /// returns the generated puzzle as a raw string,
@@ -64,6 +140,27 @@ impl GeneratedPuzzle {
 
     pub fn to_raw_string(&self) -> String {
         let difficulty_names = vec!["easy", "medium", "hard", "expert"];
+
+        //the rooms of an Inshi no heya are given per row by their products and lengths
+        if self.game_type == InshiNoHeya {
+            let rows_string: String = (0..self.dimension)
+                .map(|row| {
+                    self.groups
+                        .iter()
+                        .zip(self.results.iter())
+                        .filter(|(group, _)| group[0] / 10 == row)
+                        .map(|(group, result)| format!("{}({})", result, group.len()))
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                        + "\n"
+                })
+                .collect();
+            return format!(
+                "{} Inshi no heya of dimension {} x {}\nInshiNoHeya\n{}",
+                difficulty_names[self.difficulty], self.dimension, self.dimension, rows_string
+            );
+        }
+
         let operation_names = vec!["with only addition", "with all operations"];
 
         let mut groups_string = String::new();
@@ -82,12 +179,22 @@ impl GeneratedPuzzle {
             );
         }
 
+        //digits other than 1 to dimension are given in the section Digits
+        if self.digits != (1..=self.dimension).collect::<Vec<usize>>() {
+            groups_string = format!(
+                "{}[Digits]\n{}\n",
+                groups_string,
+                self.digits.iter().map(|d| d.to_string()).collect::<String>()
+            );
+        }
+
         format!(
-            "{} Kenken of dimension {} x {} {}\nKenKen\n{}",
+            "{} Kenken of dimension {} x {} {}\nKenKen{}\n{}",
             difficulty_names[self.difficulty],
             self.dimension,
             self.dimension,
             operation_names[self.operations_range],
+            if self.distinct_digits { " DistinctCages" } else { "" },
             groups_string
         )
     }
@@ -139,7 +246,12 @@ impl GeneratedPuzzle {
                         }
                         index_to_merge = group_field[groups[index][0] + direction * 2 - 5];
                     }
-                    if groups[index_to_merge].len() <= 2 + self.difficulty / 2 {
+                    if groups[index_to_merge].len() <= 2 + self.difficulty / 2
+                        && (!self.distinct_digits
+                            || groups[index_to_merge].iter().all(|&p| {
+                                self.solution[p] != self.solution[groups[index][0]]
+                            }))
+                    {
                         break;
                     };
                     control += 1;
@@ -171,6 +283,30 @@ impl GeneratedPuzzle {
         self.groups.sort();
     }
 
+    /// divides each row into horizontal rooms of random lengths
+    fn add_rooms(&mut self) {
+        //difficulty
+        // 0 - easy    rooms of 1 to 2 positions
+        // 1 - medium  rooms of 1 to 3 positions
+        // 2 - hard    rooms of 1 to 3 positions - less rooms of 1 position
+        // 3 - expert  rooms of 1 to 4 positions
+        let mut rng = thread_rng();
+        let max_length: usize = 2 + self.difficulty.div_ceil(2);
+
+        for row in 0..self.dimension {
+            let mut column: usize = 0;
+            while column < self.dimension {
+                let mut length = rng.gen_range(1..=max_length);
+                if length == 1 && self.difficulty == 2 {
+                    length = rng.gen_range(1..=max_length);
+                }
+                length = length.min(self.dimension - column);
+                self.groups.push((column..column + length).map(|c| row * 10 + c).collect());
+                column += length;
+            }
+        }
+    }
+
     fn check_groups(&self) -> bool {
         let mut solution_option_forward:Option<Puzzle>=None;
         let mut solution_option_backward:Option<Puzzle>=None;
@@ -195,14 +331,15 @@ impl GeneratedPuzzle {
     fn add_solution(&mut self) {
         let mut rng = thread_rng();
         let dim = self.dimension;
+        let digits = &self.digits;
 
         let mut base_field: Vec<usize> = (0..9)
             .flat_map(|shift| {
                 (0..10).map(move |digit| {
                     if shift < dim && digit < dim {
-                        (digit + shift) % dim + 1
+                        digits[(digit + shift) % dim]
                     } else {
-                        0
+                        EMPTY
                     }
                 })
             })
@@ -239,10 +376,15 @@ impl GeneratedPuzzle {
             let mut operation: char = '+';
             if digits.len() == 1 {
                 operation = 'c'
+            } else if self.game_type == InshiNoHeya {
+                operation = '*'
             } else if self.operations_range == 1 {
                 let ops_rand = rng.gen_range(0..4);
                 if digits.len() == 2 {
-                    if digits[0] % digits[1] == 0 || digits[1] % digits[0] == 0 {
+                    //no division with 0 in zero based KenKens
+                    if digits.iter().all(|&d| d > 0)
+                        && (digits[0] % digits[1] == 0 || digits[1] % digits[0] == 0)
+                    {
                         operation = ':';
                     } else {
                         match ops_rand {
@@ -285,3 +427,289 @@ impl GeneratedPuzzle {
         }
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct GeneratedSudoku {
+    difficulty: usize,
+    regions: Vec<Vec<usize>>,
+    solution: Vec<usize>,
+    givens: Vec<usize>,
+}
+
+impl GeneratedSudoku {
+    /// generates a new jigsaw sudoku, i.e. a sudoku with irregular regions instead of
+    /// the 3x3 quadrants, with a given difficulty
+    /// Input:
+    /// * difficulty [0-3] - difficulty of the generated Sudoku, influences the count of givens
+    pub fn generate_jigsaw_sudoku(difficulty: usize) -> Self {
+        //difficulty
+        // 0 - easy    36 givens
+        // 1 - medium  32 givens
+        // 2 - hard    28 givens
+        // 3 - expert  24 givens
+        // each region keeps at least 2 givens, to keep the options per region manageable
+
+        let mut new_puzzle = GeneratedSudoku {
+            difficulty,
+            regions: Vec::new(),
+            solution: Vec::new(),
+            givens: Vec::new(),
+        };
+
+        //not every layout of regions can be filled with digits, hence retry with a new layout
+        new_puzzle.add_regions();
+        while !new_puzzle.add_solution() {
+            new_puzzle.add_regions();
+        }
+        new_puzzle.remove_givens();
+
+        new_puzzle
+    }
+
+    /// returns the generated puzzle as a JSON object in the format of JsonPuzzle,
+    /// i.e. the puzzle file of to_raw_string without the internals of the generator
+    pub fn to_json(&self) -> Result<String, String> {
+        PuzzleAsString::new_from_raw_string(self.to_raw_string(), true)?.to_json()
+    }
+
+    /// returns the generated puzzle as a raw string,
+    /// which could be saved as an input file for the Sudoku solver
+    pub fn to_raw_string(&self) -> String {
+        let difficulty_names = ["easy", "medium", "hard", "expert"];
+        let region_field = self.get_region_field();
+
+        let givens_string: String = (0..9)
+            .map(|row| {
+                (0..9)
+                    .map(|column| match self.givens[row * 10 + column] {
+                        EMPTY => '-',
+                        digit => (b'0' + digit as u8) as char,
+                    })
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect();
+        let regions_string: String = (0..9)
+            .map(|row| {
+                (0..9)
+                    .map(|column| (b'1' + region_field[row * 10 + column] as u8) as char)
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect();
+
+        format!(
+            "{} jigsaw Sudoku\nSudoku\n{}[Regions]\n{}",
+            difficulty_names[self.difficulty], givens_string, regions_string
+        )
+    }
+
+    /// maps each position to the index of its region (9 for positions outside the field)
+    fn get_region_field(&self) -> Vec<usize> {
+        let mut region_field = vec![9; 90];
+        for (region_index, region) in self.regions.iter().enumerate() {
+            region.iter().for_each(|&p| region_field[p] = region_index);
+        }
+        region_field
+    }
+
+    /// creates irregular regions by swapping positions between neighbouring regions,
+    /// starting from the 3x3 quadrants.
+    /// A swap is only kept, if both regions stay connected.
+    fn add_regions(&mut self) {
+        let mut rng = thread_rng();
+
+        self.regions = (0..9)
+            .map(|quadrant| {
+                (0..9)
+                    .map(|i| (3 * (quadrant / 3) + (i / 3)) * 10 + (3 * (quadrant % 3) + (i % 3)))
+                    .collect()
+            })
+            .collect();
+
+        let mut count_swaps: usize = 0;
+        let mut attempts: usize = 0;
+        while count_swaps < 50 && attempts < 10000 {
+            attempts += 1;
+            let region_field = self.get_region_field();
+
+            //take a random position and a neighbour in a different region
+            let position = 10 * rng.gen_range(0..9) + rng.gen_range(0..9);
+            let region = region_field[position];
+            let other_region = match get_neighbours(position)
+                .into_iter()
+                .map(|p| region_field[p])
+                .filter(|&r| r != region)
+                .choose(&mut rng)
+            {
+                Some(r) => r,
+                None => continue,
+            };
+
+            //take a position of the other region bordering the first region
+            let swap_position = match self.regions[other_region]
+                .iter()
+                .filter(|&&p| {
+                    get_neighbours(p)
+                        .into_iter()
+                        .any(|n| n != position && region_field[n] == region)
+                })
+                .choose(&mut rng)
+            {
+                Some(&p) => p,
+                None => continue,
+            };
+
+            let mut new_region: Vec<usize> = self.regions[region]
+                .iter()
+                .map(|&p| if p == position { swap_position } else { p })
+                .collect();
+            let mut new_other_region: Vec<usize> = self.regions[other_region]
+                .iter()
+                .map(|&p| if p == swap_position { position } else { p })
+                .collect();
+
+            if is_connected(&new_region) && is_connected(&new_other_region) {
+                new_region.sort_unstable();
+                new_other_region.sort_unstable();
+                self.regions[region] = new_region;
+                self.regions[other_region] = new_other_region;
+                count_swaps += 1;
+            }
+        }
+    }
+
+    /// fills the field with a random valid solution for the regions,
+    /// returns false if the regions can't be filled (within a limited number of tries)
+    fn add_solution(&mut self) -> bool {
+        let region_field = self.get_region_field();
+        let mut field = vec![EMPTY; 90];
+        let mut remaining_tries: usize = 100000;
+
+        let is_filled = self.fill_field(&mut field, &region_field, &mut remaining_tries);
+        self.solution = field;
+
+        is_filled
+    }
+
+    /// recursive trial and error to fill all open positions of the field,
+    /// always continuing with the position with the fewest possible digits
+    /// gives up, if the remaining tries are used up
+    fn fill_field(
+        &self,
+        field: &mut Vec<usize>,
+        region_field: &[usize],
+        remaining_tries: &mut usize,
+    ) -> bool {
+        let mut rng = thread_rng();
+        if *remaining_tries == 0 {
+            return false;
+        }
+        *remaining_tries -= 1;
+
+        let mut next_position: Option<(usize, Vec<usize>)> = None;
+        for position in (0..89).filter(|&p| p % 10 < 9 && field[p] == EMPTY) {
+            let row = position - position % 10;
+            let used_digits: Vec<usize> = (row..row + 9)
+                .chain((position % 10..90).step_by(10))
+                .chain(self.regions[region_field[position]].iter().copied())
+                .map(|p| field[p])
+                .collect();
+            let digits: Vec<usize> = (1..10).filter(|d| !used_digits.contains(d)).collect();
+
+            if next_position
+                .as_ref()
+                .is_none_or(|(_, best_digits)| digits.len() < best_digits.len())
+            {
+                next_position = Some((position, digits));
+            }
+        }
+
+        let (position, mut digits) = match next_position {
+            Some(next) => next,
+            None => return true,
+        };
+
+        digits.shuffle(&mut rng);
+        for digit in digits {
+            field[position] = digit;
+            if self.fill_field(field, region_field, remaining_tries) {
+                return true;
+            }
+        }
+        field[position] = EMPTY;
+
+        false
+    }
+
+    /// removes givens from the solution in random order,
+    /// as long as the puzzle stays uniquely solvable
+    fn remove_givens(&mut self) {
+        let mut rng = thread_rng();
+        let max_givens: usize = 36 - 4 * self.difficulty;
+        let region_field = self.get_region_field();
+
+        self.givens = self.solution.clone();
+        let mut count_givens: usize = 81;
+
+        let mut positions: Vec<usize> = (0..89).filter(|p| p % 10 < 9).collect();
+        positions.shuffle(&mut rng);
+
+        for position in positions {
+            if count_givens <= max_givens {
+                break;
+            }
+            let region_givens = self.regions[region_field[position]]
+                .iter()
+                .filter(|&&p| self.givens[p] != EMPTY)
+                .count();
+            if region_givens <= 2 {
+                continue;
+            }
+
+            self.givens[position] = EMPTY;
+            if self.check_unique_solution() {
+                count_givens -= 1;
+            } else {
+                self.givens[position] = self.solution[position];
+            }
+        }
+    }
+
+    /// solves the puzzle in normal and reversed group direction,
+    /// both solutions must match the generated solution
+    fn check_unique_solution(&self) -> bool {
+        [true, false].iter().all(|&normal_group_direction| {
+            PuzzleAsString::new_from_raw_string(self.to_raw_string(), normal_group_direction)
+                .and_then(Puzzle::new_from_puzzle_file)
+                .ok()
+                .and_then(|puzzle| puzzle.solve())
+                .is_some_and(|solved| {
+                    solved
+                        .solution()
+                        .iter()
+                        .zip(self.solution.iter())
+                        .all(|(found, expected)| found == expected)
+                })
+        })
+    }
+}
+
+/// returns the orthogonal neighbours of a position within a 9x9 field
+fn get_neighbours(position: usize) -> Vec<usize> {
+    let (row, column) = (position / 10, position % 10);
+    let mut neighbours = Vec::new();
+    if row > 0 {
+        neighbours.push(position - 10)
+    };
+    if row < 8 {
+        neighbours.push(position + 10)
+    };
+    if column > 0 {
+        neighbours.push(position - 1)
+    };
+    if column < 8 {
+        neighbours.push(position + 1)
+    };
+    neighbours
+}
@@ -4,8 +4,11 @@
 //! a given mathematical operation.
 //! The Kenken puzzle consists of a set of groups. This set outlays the n x n kenken field
 //!
-//! For Sudoku, the groups are the open 3x3 subfield, which also need to hold disjunctive digits.
+//! For Sudoku, the groups are the open regions (3x3 subfields or the irregular regions of a
+//! jigsaw Sudoku), which also need to hold disjunctive digits.
 //! (the given constants are not part of these groups).
+//! For Futoshiki and Skyscrapers, the groups are the open positions of each row.
+//! For Kakuro, the groups are the across and down runs, i.e. each position is part of two groups.
 //!
 use std::collections::HashSet;
 
@@ -13,6 +16,8 @@ use itertools::Itertools;
 use permutohedron::heap_recursive;
 
 use crate::kk_block_list::BlockList;
+use crate::kk_constraint::ConstraintList;
+use crate::kk_puzzle::EMPTY;
 
 /// Struct group describes a single group
 /// A group consists of
@@ -21,6 +26,8 @@ use crate::kk_block_list::BlockList;
 /// * the (vector of) single positions within the kenken puzzle belonging to the group
 /// * the (vector of) the possible options (solutions) for the group fulfilling the operation
 ///   (the solution contains exactly one option)
+/// * a marker, if the digits of the group must be distinct (killer-style cage),
+///   otherwise digits might be repeated as long as they are not in the same row or column
 /// * a marker, if the group is one dimensional, i.e. all positions are in exactly one row or column
 /// * a marker, if the digits of the (one dimensional) group are already added to the blocklist
 ///   of the puzzle.
@@ -36,6 +43,7 @@ pub struct Group {
     operation: char,
     positions: Vec<usize>,
     options: Vec<Vec<usize>>,
+    has_distinct_digits: bool,
     is_one_dimensional: bool,
     is_already_in_block_list: bool,
 }
@@ -43,26 +51,76 @@ pub struct Group {
 impl Group {
     /// Creates a new group for a Sudoku puzzle
     /// Input:
-    ///  * positions - the unset/looked for positions in a 3x3 subfield
-    ///  * constants - the given constants in the same 3x3 subfield
+    ///  * positions - the unset/looked for positions in a region (3x3 subfield or jigsaw region)
+    ///  * constants - the given constants in the same region
+    ///  * digits - the digits of the puzzle, usually 1 to 9
+    ///  * normal_group_direction - false reverses the order of the options
     ///
     /// Returns: a result of
     ///  * a new group, if valid options are available or
     ///  * an error String otherwise
     ///
-    /// The valid options are all permutations of the digits from 1 to 9
+    /// The valid options are all permutations of the digits
     /// that are not part of the given constants
     ///
-    pub fn new_sudoku(positions: &Vec<usize>, constants: &HashSet<usize>) -> Result<Self, String> {
-        let mut data: Vec<usize>;
-        let mut options: Vec<Vec<usize>> = Vec::new();
+    pub fn new_sudoku(
+        positions: &Vec<usize>,
+        constants: &HashSet<usize>,
+        digits: &[usize],
+        normal_group_direction: bool,
+    ) -> Result<Self, String> {
+        let data: Vec<usize> = digits.iter().copied().filter(|d| !constants.contains(d)).collect();
+
+        Group::new_from_permutations(data, positions, normal_group_direction)
+    }
 
-        data = (1..10).filter(|d| !constants.contains(d)).collect();
+    /// Creates a new group for a row of a latin square puzzle, e.g. Futoshiki or Skyscrapers
+    /// Input:
+    ///  * digits - the digits of the puzzle, usually 1 to dimension
+    ///  * positions - the unset/looked for positions in the row
+    ///  * constants - the given constants in the same row
+    ///  * normal_group_direction - false reverses the order of the options
+    ///
+    /// Returns: a result of
+    ///  * a new group, if valid options are available or
+    ///  * an error String otherwise
+    ///
+    /// The valid options are all permutations of the digits
+    /// that are not part of the given constants.
+    /// If the row has less open positions than digits (e.g. the black positions of a Str8ts),
+    /// the options are all arrangements of these digits on the open positions.
+    ///
+    pub fn new_latin_row(
+        digits: &[usize],
+        positions: &[usize],
+        constants: &HashSet<usize>,
+        normal_group_direction: bool,
+    ) -> Result<Self, String> {
+        let data: Vec<usize> = digits.iter().copied().filter(|d| !constants.contains(d)).collect();
+
+        Group::new_from_permutations(data, positions, normal_group_direction)
+    }
 
-        heap_recursive(&mut data, |p| {
-            //permutations.push(p.iter().fold(0,|s,d| s*10+d))
-            options.push(p.to_vec())
-        });
+    /// Creates a new group with all permutations of the given digits as options,
+    /// if there are more digits than positions, with all arrangements of the digits
+    fn new_from_permutations(
+        mut data: Vec<usize>,
+        positions: &[usize],
+        normal_group_direction: bool,
+    ) -> Result<Self, String> {
+        let mut options: Vec<Vec<usize>> = Vec::new();
+
+        if data.len() > positions.len() {
+            options = data.into_iter().permutations(positions.len()).collect();
+        } else {
+            heap_recursive(&mut data, |p| {
+                //permutations.push(p.iter().fold(0,|s,d| s*10+d))
+                options.push(p.to_vec())
+            });
+        }
+        if !normal_group_direction {
+            options.reverse();
+        }
 
         //result and operation are not relevant for Sudoku, hence the group
         //is constructed with placeholder values
@@ -73,22 +131,79 @@ impl Group {
             operation: 's',
             result: 0,
             options,
+            has_distinct_digits: true,
             is_already_in_block_list: true,
             is_one_dimensional: false,
-            positions: positions.clone(),
+            positions: positions.to_vec(),
         };
 
         if new_group.options.len() > 0 {
             Ok(new_group)
         } else {
-            Err("Can't find valid options for Sudoku group!".to_string())
+            Err("Can't find valid options for group of disjunctive digits!".to_string())
+        }
+    }
+
+    /// Creates a new group for a run of a Kakuro puzzle
+    /// Input:
+    ///  * digits - the digits of the puzzle, usually 1 to 9
+    ///  * result - the sum of the run given by the clue
+    ///  * positions - the positions of the run
+    ///  * normal_group_direction - false reverses the order of the options
+    ///
+    /// Returns: a result of
+    ///  * a new group, if valid options are available or
+    ///  * an error String otherwise
+    ///
+    /// The valid options are all permutations of the combinations of distinct digits
+    /// with the given sum
+    ///
+    pub fn new_kakuro(
+        digits: &[usize],
+        result: usize,
+        positions: &[usize],
+        normal_group_direction: bool,
+    ) -> Result<Self, String> {
+        let mut options: Vec<Vec<usize>> = digits
+            .iter()
+            .copied()
+            .combinations(positions.len())
+            .filter(|combination| combination.iter().sum::<usize>() == result)
+            .flat_map(|combination| combination.into_iter().permutations(positions.len()))
+            .collect();
+        if !normal_group_direction {
+            options.reverse();
+        }
+
+        //the rows and columns of a Kakuro may repeat digits outside the runs,
+        //hence the digits of a run must not be blocklisted
+        let new_group = Group {
+            operation: '+',
+            result,
+            options,
+            has_distinct_digits: true,
+            is_already_in_block_list: true,
+            is_one_dimensional: true,
+            positions: positions.to_vec(),
+        };
+
+        if new_group.options.is_empty() {
+            Err(format!(
+                "No valid options for Kakuro run with sum {} found: {:?}",
+                result, positions
+            ))
+        } else {
+            Ok(new_group)
         }
     }
 
     /// Creates a new group for a Kenken puzzle
     /// Input:
-    ///  * dimension - the dimension of the KenKen puzzle
+    ///  * digits - the digits of the KenKen puzzle, usually 1 to dimension
     ///  * group_as_string - a string describing the group. The string is loaded from the input.
+    ///    A leading '!' marks a group, whose digits must be distinct.
+    ///  * normal_group_direction - false reverses the order of the options
+    ///  * distinct_digits - the digits of the group must be distinct, even without leading '!'
     ///
     /// Returns: a result of
     ///  * a new group, if string could be parsed and valid options are available or
@@ -96,15 +211,23 @@ impl Group {
     ///
     /// First, the group_as_string is parsed into positions, result and operation
     /// Afterward the valid options are added
-    /// as all combinations of digits 1 to dimension of the puzzle and
+    /// as all combinations of digits of the puzzle and
     /// fulfilling the given operation with the given result.
 
-    pub fn new_kenken(dimension: usize, group_as_string: &str, normal_group_direction:bool) -> Result<Self, String> {
+    pub fn new_kenken(
+        digits: &[usize],
+        group_as_string: &str,
+        normal_group_direction: bool,
+        distinct_digits: bool,
+    ) -> Result<Self, String> {
+        let has_distinct_digits = distinct_digits || group_as_string.starts_with('!');
+
         //parse the input line into a vec of usize containing
         // the result at index 0,
         // the (encoded) operation at index 1 and
         // the positions from index 2 till the end
         let mut positions: Vec<usize> = group_as_string
+            .trim_start_matches('!')
             .chars()
             //map operations to ids and insert separators
             .map(|c| match c {
@@ -137,6 +260,7 @@ impl Group {
                 operation,
                 result,
                 options: Vec::new(),
+                has_distinct_digits,
                 is_already_in_block_list: true,
                 //check if all positions are in one line or column if yes
                 //the group is one dimensional
@@ -154,8 +278,8 @@ impl Group {
             new_group.is_already_in_block_list = !new_group.is_one_dimensional;
             //use multi_cartesian_product to get all possible combinations with repetition
             new_group.options = (0..new_group.positions.len())
-                .map(|_| (1..=dimension)
-                    .map(|i| if normal_group_direction{i} else {dimension+1-i}))
+                .map(|_| (0..digits.len())
+                    .map(|i| if normal_group_direction{digits[i]} else {digits[digits.len()-1-i]}))
                 .multi_cartesian_product()
                 .filter(|option| new_group.is_valid_option(option))
                 .collect();
@@ -181,12 +305,26 @@ impl Group {
             operation: self.operation,
             result: self.result,
             positions: self.positions.clone(),
+            has_distinct_digits: self.has_distinct_digits,
             is_one_dimensional: self.is_one_dimensional,
             is_already_in_block_list: new_is_block_listed,
             options: new_options.clone(),
         }
     }
 
+    /// Converts a KenKen group back to its cage line, e.g. "!12+00.01.11",
+    /// a group with distinct digits gets a leading '!'
+    pub fn to_cage_string(&self) -> String {
+        let positions: Vec<String> = self.positions.iter().map(|p| format!("{:02}", p)).collect();
+        format!(
+            "{}{}{}{}",
+            if self.has_distinct_digits { "!" } else { "" },
+            self.result,
+            self.operation,
+            positions.join(".")
+        )
+    }
+
     /// Adds the option with index option_nr to the given field
     /// no validation is done
     /// the return value indicates success (true) or failure (false),
@@ -200,10 +338,13 @@ impl Group {
             });
     }
 
-    /// Validates the options of a group against a given field and blocklist
+    /// Validates the options of a group against a given field, blocklist and constraints
     /// Inputs:
     ///  * field - the current representation of the puzzle solution
     ///  * blocklist - the current blocklist for the field positions
+    ///  * constraints - the additional constraints of the puzzle, e.g. inequalities
+    ///  * has_unique_lines - the digits of each row and column must be distinct
+    ///    (false for Kakuro, where only the digits of a run are distinct)
     ///
     /// Returns:
     ///  * the number of available options for this group after the validation
@@ -214,6 +355,8 @@ impl Group {
         &self,
         field: &Vec<usize>,
         block_list: &mut BlockList,
+        constraints: &ConstraintList,
+        has_unique_lines: bool,
     ) -> (usize, usize, Self) {
         //current options to be validated
         let mut new_options = self.options.clone();
@@ -221,21 +364,32 @@ impl Group {
 
         //for each position
         for index in 0..self.positions.len() {
+            //the positions of the grids of a multi grid Sudoku are offset by 100 per grid
+            let grid = self.positions[index] - self.positions[index] % 100;
             let column = self.positions[index] % 10;
             let row = self.positions[index] - column;
 
+            //positions shared with another group (e.g. the across and down runs of a Kakuro)
+            //might already be set by the other group, only this digit is valid
+            let digit = field[self.positions[index]];
+            if digit != EMPTY {
+                new_options.retain(|option| option[index] == digit);
+                continue;
+            }
+
             //get the blocklisted digits for the current position
             let mut position_block_list: HashSet<usize> =
                 block_list.get_position_block_list(&self.positions[index]);
 
             //get the existing digits in the col and row of the current position
             //add those digits to the position blocklist
-
-            (row..row + 9)
-                .chain((column..90).step_by(10))
-                .map(|i| field[i]) //change index to digit
-                .filter(|&digit| digit > 0) //get existing values
-                .for_each(|digit| _ = position_block_list.insert(digit));
+            if has_unique_lines {
+                (row..row + 9)
+                    .chain((grid + column..grid + 90).step_by(10))
+                    .map(|i| field[i]) //change index to digit
+                    .filter(|&digit| digit != EMPTY) //get existing values
+                    .for_each(|digit| _ = position_block_list.insert(digit));
+            }
 
             //filter out all digits from the positional blocklist
             new_options = new_options
@@ -244,6 +398,11 @@ impl Group {
                 .collect();
         }
 
+        //filter out all options violating the constraints
+        if !constraints.is_empty() {
+            new_options = constraints.filter_options(&self.positions, new_options, field);
+        }
+
         //Update the blocklist if new unique values for a one-dimensional group are found
         if !self.is_already_in_block_list && new_options.len() > 1 {
             is_block_listed =
@@ -258,7 +417,8 @@ impl Group {
     }
 
     /// Validates if the candidate is a valid option for a KenKen group, i.e.,
-    /// contains no duplicates in the same row or column and
+    /// contains no duplicates in the same row or column (no duplicates at all
+    /// for groups with distinct digits) and
     /// fulfills the mathematical operation
     fn is_valid_option(&self, candidate: &Vec<usize>) -> bool {
         let dimension = candidate.len();
@@ -272,7 +432,8 @@ impl Group {
             .filter(|(pi, di)| candidate[*pi] == candidate[*di])
             //check that these positions are not on the same row or column
             .any(|(pi, di)| {
-                self.positions[pi] / 10 == self.positions[di] / 10
+                self.has_distinct_digits
+                    || self.positions[pi] / 10 == self.positions[di] / 10
                     || self.positions[pi] % 10 == self.positions[di] % 10
             })
         {
@@ -305,7 +466,7 @@ mod kk_groups_tests {
 
     #[test]
     fn check_new_kenken() {
-        let group = Group::new_kenken(4, "6*10.11.20", true).unwrap();
+        let group = Group::new_kenken(&[1, 2, 3, 4], "6*10.11.20", true, false).unwrap();
         assert_eq!(group.is_one_dimensional, false);
         assert_eq!(group.is_already_in_block_list, true);
         assert_eq!(group.positions, vec!(10, 11, 20));
@@ -323,7 +484,7 @@ mod kk_groups_tests {
             )
         );
 
-        let group = Group::new_kenken(5, "4-20.30", true).unwrap();
+        let group = Group::new_kenken(&[1, 2, 3, 4, 5], "4-20.30", true, false).unwrap();
         assert_eq!(group.is_one_dimensional, true);
         assert_eq!(group.is_already_in_block_list, false);
         assert_eq!(group.positions, vec!(20, 30));
@@ -331,7 +492,7 @@ mod kk_groups_tests {
         assert_eq!(group.result, 4);
         assert_eq!(group.options, vec!(vec!(1, 5), vec!(5, 1)));
 
-        let group = Group::new_kenken(8, "21+41.42.43", true).unwrap();
+        let group = Group::new_kenken(&[1, 2, 3, 4, 5, 6, 7, 8], "21+41.42.43", true, false).unwrap();
         assert_eq!(group.is_one_dimensional, true);
         assert_eq!(group.is_already_in_block_list, false);
         assert_eq!(group.positions, vec!(41, 42, 43));
@@ -349,7 +510,28 @@ mod kk_groups_tests {
             )
         );
 
-        assert_eq!(Group::new_kenken(9, "22/.01.02",true).is_err(), true);
+        assert_eq!(Group::new_kenken(&[1, 2, 3, 4, 5, 6, 7, 8, 9], "22/.01.02",true, false).is_err(), true);
+
+        //zero based digits, each option with a 0 fulfills the multiplication
+        let group = Group::new_kenken(&[0, 1, 2, 3], "0*00.01", true, false).unwrap();
+        assert_eq!(
+            group.options,
+            vec!(vec!(0, 1), vec!(0, 2), vec!(0, 3), vec!(1, 0), vec!(2, 0), vec!(3, 0))
+        );
+
+        //L-shaped group, the digits at 10 and 21 might be equal
+        let group = Group::new_kenken(&[1, 2, 3, 4], "7+10.11.21", true, false).unwrap();
+        assert!(!group.has_distinct_digits);
+        assert!(group.options.contains(&vec!(3, 1, 3)));
+
+        //same group with distinct digits, marked by "!" or by the puzzle rule
+        let group = Group::new_kenken(&[1, 2, 3, 4], "!7+10.11.21", true, false).unwrap();
+        assert!(group.has_distinct_digits);
+        assert_eq!(group.positions, vec!(10, 11, 21));
+        assert!(!group.options.contains(&vec!(3, 1, 3)));
+        assert_eq!(group.options.len(), 6);
+        let group = Group::new_kenken(&[1, 2, 3, 4], "7+10.11.21", true, true).unwrap();
+        assert_eq!(group.options.len(), 6);
     }
 
     #[test]
@@ -357,12 +539,35 @@ mod kk_groups_tests {
         let positions: Vec<usize> = vec![3, 4];
         let constants: HashSet<usize> = vec![1, 2, 3, 4, 5, 6, 7].into_iter().collect();
 
-        let group = Group::new_sudoku(&positions, &constants).unwrap();
+        let group = Group::new_sudoku(&positions, &constants, &[1, 2, 3, 4, 5, 6, 7, 8, 9], true).unwrap();
         assert_eq!(group.is_one_dimensional, false);
         assert_eq!(group.is_already_in_block_list, true);
         assert_eq!(group.positions, vec!(3, 4));
         assert_eq!(group.operation, 's');
         assert_eq!(group.result, 0);
         assert_eq!(group.options, vec!(vec!(8, 9), vec!(9, 8)));
+
+        let group = Group::new_sudoku(&positions, &constants, &[1, 2, 3, 4, 5, 6, 7, 8, 9], false).unwrap();
+        assert_eq!(group.options, vec!(vec!(9, 8), vec!(8, 9)));
+    }
+
+    #[test]
+    fn check_new_kakuro() {
+        let digits: Vec<usize> = (1..=9).collect();
+        let group = Group::new_kakuro(&digits, 4, &[11, 12], true).unwrap();
+        assert_eq!(group.operation, '+');
+        assert!(group.has_distinct_digits);
+        assert!(group.is_already_in_block_list);
+        assert_eq!(group.options, vec!(vec!(1, 3), vec!(3, 1)));
+        assert!(Group::new_kakuro(&digits, 2, &[11, 12], true).is_err());
+
+        //digits in the same row are not blocked, only a digit set by a crossing run is valid
+        let mut field = vec![EMPTY; 90];
+        field[13] = 3;
+        field[12] = 1;
+        let (option_count, _, updated) =
+            group.get_updated_group(&field, &mut BlockList::new(), &ConstraintList::new(), false);
+        assert_eq!(option_count, 1);
+        assert_eq!(updated.options, vec!(vec!(3, 1)));
     }
 }
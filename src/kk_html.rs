@@ -0,0 +1,379 @@
+//! The module kk_html is part of kenken_solve
+//! and imports KenKen puzzles from locally saved HTML pages, e.g. of newdoku.com
+//!
+//! The puzzle is the first table of the page with 3 x 3 to 9 x 9 cells (td elements).
+//! The borders of the cages are the thick borders of the cells, marked either
+//! * by the inline style, e.g. "border-right: 3px solid black" (at least 2px, "medium" or "thick") or
+//! * by a class naming the side together with "thick", "bold" or "cage", e.g. "thick-right".
+//!
+//! The clue of a cage is the text of one of its cells, i.e. the result followed by the operation
+//! ('+', '-' or '−', '*', 'x' or '×', ':', '/' or '÷'), a result without operation is a constant.
+//! The texts of inline tags are joined, e.g. "<span>16</span>×" is the clue "16×".
+//!
+
+const SIDES: [&str; 4] = ["top", "right", "bottom", "left"];
+/// Tags within a text, e.g. the result of a clue in a span followed by the operation
+const INLINE_TAGS: [&str; 9] = ["span", "b", "i", "em", "strong", "font", "sup", "sub", "small"];
+
+/// A cell of the puzzle table with its thick borders (top, right, bottom, left) and its clue
+struct Cell {
+    borders: [bool; 4],
+    clue: Option<String>,
+}
+
+/// Converts a saved HTML page with a KenKen to the text of a KenKen file,
+/// the description names the id of the puzzle, if the page contains it (e.g. "?id=1278350")
+pub fn get_kenken_from_html(html: &str) -> Result<String, String> {
+    let cells = get_puzzle_cells(html)?;
+    let dimension = (1..=9).find(|d| d * d == cells.len()).unwrap_or(0);
+    if !cells.iter().any(|cell| cell.borders.contains(&true)) {
+        return Err("No cage borders found in the puzzle table".to_string());
+    }
+
+    //cells without a border between them belong to the same cage
+    let mut cage_of_cell: Vec<usize> = (0..cells.len()).collect();
+    for (index, cell) in cells.iter().enumerate() {
+        let (row, column) = (index / dimension, index % dimension);
+        let neighbours = [
+            (column + 1 < dimension, index + 1, 1),
+            (row + 1 < dimension, index + dimension, 2),
+        ];
+        for (exists, neighbour, side) in neighbours {
+            if exists && !cell.borders[side] && !cells[neighbour].borders[(side + 2) % 4] {
+                let (cage, other_cage) = (cage_of_cell[index], cage_of_cell[neighbour]);
+                let merged_cage = cage.min(other_cage);
+                cage_of_cell
+                    .iter_mut()
+                    .filter(|c| **c == cage || **c == other_cage)
+                    .for_each(|c| *c = merged_cage);
+            }
+        }
+    }
+
+    let mut cage_lines: Vec<String> = Vec::new();
+    let mut problems: Vec<String> = Vec::new();
+    //each cage is numbered by its first cell, so the cages are ordered by their first cell
+    let mut cages: Vec<usize> = cage_of_cell.clone();
+    cages.sort_unstable();
+    cages.dedup();
+    for cage in cages {
+        let positions: Vec<usize> = (0..cells.len())
+            .filter(|&i| cage_of_cell[i] == cage)
+            .collect();
+        let position_string: Vec<String> = positions
+            .iter()
+            .map(|&i| format!("{}{}", i / dimension, i % dimension))
+            .collect();
+        let clues: Vec<&String> = positions
+            .iter()
+            .filter_map(|&i| cells[i].clue.as_ref())
+            .collect();
+        match clues[..] {
+            [clue] => {
+                //a result without operation is a constant
+                let clue = if clue.ends_with(|c: char| c.is_ascii_digit()) {
+                    format!("{}c", clue)
+                } else {
+                    clue.clone()
+                };
+                cage_lines.push(format!("{}{}", clue, position_string.join(".")));
+            }
+            _ => problems.push(format!(
+                "Cage {} must have one clue, found {}",
+                position_string.join("."),
+                clues.len()
+            )),
+        }
+    }
+    if !problems.is_empty() {
+        return Err(problems.join("\n"));
+    }
+
+    let description = match get_puzzle_id(html) {
+        Some(id) => format!(
+            "Newdoku.com KenKen-puzzle nr.: {} with Dim {} x {}\nsource: newdoku.com",
+            id, dimension, dimension
+        ),
+        None => format!(
+            "KenKen with Dim {} x {} imported from HTML",
+            dimension, dimension
+        ),
+    };
+
+    Ok(format!(
+        "{}\nKenKen\n{}\n",
+        description,
+        cage_lines.join("\n")
+    ))
+}
+
+/// Retrieves the cells of the first table with a square count of 9 to 81 cells
+fn get_puzzle_cells(html: &str) -> Result<Vec<Cell>, String> {
+    let lowercase_html = html.to_ascii_lowercase();
+    for (table_start, _) in lowercase_html.match_indices("<table") {
+        let table_end = lowercase_html[table_start..]
+            .find("</table")
+            .map_or(html.len(), |end| table_start + end);
+        let table = &html[table_start..table_end];
+        let cell_starts: Vec<usize> = table
+            .to_ascii_lowercase()
+            .match_indices("<td")
+            .map(|(start, _)| start)
+            .collect();
+        if !(3..=9).any(|d| d * d == cell_starts.len()) {
+            continue;
+        }
+
+        let cell_ends = cell_starts
+            .iter()
+            .skip(1)
+            .copied()
+            .chain(std::iter::once(table.len()));
+        return Ok(cell_starts
+            .iter()
+            .zip(cell_ends)
+            .map(|(&start, end)| get_cell(&table[start..end]))
+            .collect());
+    }
+
+    Err("No puzzle table with 3 x 3 to 9 x 9 cells found".to_string())
+}
+
+/// Parses a cell from the text of its td element up to the next cell
+fn get_cell(cell_html: &str) -> Cell {
+    let tag_end = cell_html.find('>').unwrap_or(cell_html.len());
+    let tag = cell_html[..tag_end].to_ascii_lowercase();
+    let style = get_attribute(&tag, "style");
+    let classes = get_attribute(&tag, "class");
+
+    let mut borders = [false; 4];
+    for declaration in style.split(';') {
+        let Some((property, value)) = declaration.split_once(':') else {
+            continue;
+        };
+        let property = property.trim();
+        for (side, border) in SIDES.iter().zip(borders.iter_mut()) {
+            let side_property = format!("border-{}", side);
+            let is_side =
+                property == side_property || property == format!("{}-width", side_property);
+            if (is_side || property == "border" || property == "border-width") && is_thick(value) {
+                *border = true;
+            }
+        }
+    }
+    for class in classes.split_whitespace() {
+        for (side, border) in SIDES.iter().zip(borders.iter_mut()) {
+            if class.contains(side)
+                && ["thick", "bold", "cage"]
+                    .iter()
+                    .any(|mark| class.contains(mark))
+            {
+                *border = true;
+            }
+        }
+    }
+
+    let content = cell_html.get(tag_end + 1..).unwrap_or("");
+    let content = content.split("</td").next().unwrap_or("");
+    Cell {
+        borders,
+        clue: get_text(content).split_whitespace().find_map(get_clue),
+    }
+}
+
+/// Retrieves the value of an attribute of a tag, e.g. the style of a cell
+fn get_attribute<'a>(tag: &'a str, name: &str) -> &'a str {
+    for quote in ['"', '\''] {
+        if let Some(start) = tag.find(&format!("{}={}", name, quote)) {
+            let value = &tag[start + name.len() + 2..];
+            return value.split(quote).next().unwrap_or("");
+        }
+    }
+    ""
+}
+
+/// Checks a border width, i.e. "thick", "medium" or at least 2px
+fn is_thick(value: &str) -> bool {
+    value.split_whitespace().any(|token| {
+        token == "thick"
+            || token == "medium"
+            || token
+                .strip_suffix("px")
+                .and_then(|width| width.parse::<f64>().ok())
+                .is_some_and(|width| width >= 2.0)
+    })
+}
+
+/// Removes the tags of the content and replaces the entities of the operations,
+/// the texts of inline tags are joined, e.g. "<span>16</span>×" to "16×"
+fn get_text(content: &str) -> String {
+    let mut text = String::new();
+    let mut tag: Option<String> = None;
+    for c in content.chars() {
+        match (c, tag.as_mut()) {
+            ('<', None) => tag = Some(String::new()),
+            ('>', Some(tag_text)) => {
+                let tag_name: String = tag_text
+                    .trim_start_matches('/')
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric())
+                    .collect::<String>()
+                    .to_ascii_lowercase();
+                if !INLINE_TAGS.contains(&&tag_name[..]) {
+                    text.push(' ');
+                }
+                tag = None;
+            }
+            (_, Some(tag_text)) => tag_text.push(c),
+            (_, None) => text.push(c),
+        }
+    }
+    [
+        ("&times;", "*"),
+        ("&#215;", "*"),
+        ("&divide;", ":"),
+        ("&#247;", ":"),
+        ("&minus;", "-"),
+        ("&#8722;", "-"),
+        ("&nbsp;", " "),
+    ]
+    .iter()
+    .fold(text, |text, (entity, replacement)| {
+        text.replace(entity, replacement)
+    })
+}
+
+/// Converts a text to a clue of the KenKen format, e.g. "16×" to "16*",
+/// None if the text is no clue, e.g. a pencil mark
+fn get_clue(text: &str) -> Option<String> {
+    let digit_count = text.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digit_count == 0 {
+        return None;
+    }
+    let operation = match text[digit_count..].chars().collect::<Vec<char>>()[..] {
+        [] => "",
+        ['+'] => "+",
+        ['-' | '−'] => "-",
+        ['*' | 'x' | '×'] => "*",
+        [':' | '/' | '÷'] => ":",
+        _ => return None,
+    };
+    Some(format!("{}{}", &text[..digit_count], operation))
+}
+
+/// Retrieves the id of the puzzle from a link of the page, e.g. "online.php?id=1278350"
+fn get_puzzle_id(html: &str) -> Option<&str> {
+    let (_, id_start) = html.split_once("?id=")?;
+    let id_length = id_start.chars().take_while(|c| c.is_ascii_digit()).count();
+    Some(&id_start[..id_length]).filter(|id| !id.is_empty())
+}
+
+#[cfg(test)]
+mod kk_html_tests {
+    use super::*;
+    use crate::kk_load::PuzzleAsString;
+
+    /// Creates the HTML of a grid with the given cage map, the clue of each cage
+    /// in the first of its cells and thick borders given as inline styles or classes
+    fn get_test_html(cage_map: &[&str], clues: &[(char, &str)], with_classes: bool) -> String {
+        let rows: Vec<Vec<char>> = cage_map.iter().map(|row| row.chars().collect()).collect();
+        let mut html = String::from(
+            "<html><head><link rel=\"canonical\" href=\"https://newdoku.com/include/online.php?id=1278350\">\
+             </head><body><table class=\"menu\"><tr><td>Menu</td></tr></table><table>",
+        );
+        let mut seen: Vec<char> = Vec::new();
+        for (row, cages) in rows.iter().enumerate() {
+            html.push_str("<tr>");
+            for (column, &cage) in cages.iter().enumerate() {
+                let right = column + 1 < cages.len() && cages[column + 1] != cage;
+                let bottom = row + 1 < rows.len() && rows[row + 1][column] != cage;
+                let attributes = if with_classes {
+                    format!(
+                        " class=\"cell{}{}\"",
+                        if right { " thick-right" } else { "" },
+                        if bottom { " thick-bottom" } else { "" }
+                    )
+                } else {
+                    format!(
+                        " style='border-right: {}px solid; border-bottom-width: {}px'",
+                        if right { 3 } else { 1 },
+                        if bottom { 3 } else { 1 }
+                    )
+                };
+                let clue = if seen.contains(&cage) {
+                    ""
+                } else {
+                    clues
+                        .iter()
+                        .find(|(c, _)| *c == cage)
+                        .map_or("", |(_, clue)| clue)
+                };
+                seen.push(cage);
+                html.push_str(&format!(
+                    "<td{}><div class=\"clue\">{}</div><span>&nbsp;</span></td>",
+                    attributes, clue
+                ));
+            }
+            html.push_str("</tr>");
+        }
+        html + "</table></body></html>"
+    }
+
+    #[test]
+    fn check_import_from_html() {
+        let cage_map = ["AABB", "CCBD", "CEFD", "EEFF"];
+        let clues = [
+            ('A', "1&minus;"),
+            ('B', "8+"),
+            ('C', "6&times;"),
+            ('D', "2−"),
+            ('E', "<span>16</span>×"),
+            ('F', "6+"),
+        ];
+        let kenken = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
+        for with_classes in [false, true] {
+            let html = get_test_html(&cage_map, &clues, with_classes);
+            let raw_puzzle_string = get_kenken_from_html(&html).unwrap();
+            let imported = PuzzleAsString::new_from_raw_string(raw_puzzle_string, true).unwrap();
+            assert_eq!(imported.puzzle_string(), kenken.puzzle_string());
+            assert_eq!(imported.description(), kenken.description());
+        }
+
+        let constant_html = get_test_html(
+            &["AAB", "CDB", "CEE"],
+            &[
+                ('A', "3+"),
+                ('B', "4:"),
+                ('C', "5+"),
+                ('D', "2"),
+                ('E', "3/"),
+            ],
+            false,
+        );
+        assert_eq!(
+            get_kenken_from_html(&constant_html).unwrap(),
+            "Newdoku.com KenKen-puzzle nr.: 1278350 with Dim 3 x 3\nsource: newdoku.com\nKenKen\n3+00.01\n4:02.12\n5+10.20\n2c11\n3:21.22\n"
+        );
+
+        let missing_clue = get_test_html(&cage_map, &clues[..5], false);
+        assert_eq!(
+            get_kenken_from_html(&missing_clue).unwrap_err(),
+            "Cage 22.32.33 must have one clue, found 0"
+        );
+        assert!(get_kenken_from_html("<table><tr><td>1+</td></tr></table>").is_err());
+        let no_borders = "<table>".to_string() + &"<td>1</td>".repeat(9) + "</table>";
+        assert!(get_kenken_from_html(&no_borders).is_err());
+    }
+
+    #[test]
+    fn check_import_of_saved_page() {
+        //a hand-written page in the layout of a saved newdoku.com page
+        let imported = PuzzleAsString::new_from_file("KK-Newdoku-1.html").unwrap();
+        let kenken = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
+        assert_eq!(imported.puzzle_string(), kenken.puzzle_string());
+        assert_eq!(imported.description(), kenken.description());
+
+        assert_eq!(get_text("<div><span>16</span>&times;</div>").trim(), "16*");
+        assert_eq!(get_text("<div>1</div><div>2</div>").split_whitespace().count(), 2);
+    }
+}
@@ -1,24 +1,169 @@
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fs;
+use std::io::{self, Read};
 
-use GameType::{KenKen, Sudoku};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+use crate::kk_diagnostic::get_diagnostics;
+use crate::kk_html::get_kenken_from_html;
+
+use GameType::{
+    Futoshiki, InshiNoHeya, Kakuro, KenKen, MultiSudoku, Skyscrapers, Str8ts, Sudoku,
+};
+
+/// The game types by their name in the second line of a puzzle file
+pub const GAME_TYPES: [(&str, GameType); 8] = [
+    ("KenKen", KenKen),
+    ("Sudoku", Sudoku),
+    ("Futoshiki", Futoshiki),
+    ("Skyscrapers", Skyscrapers),
+    ("Kakuro", Kakuro),
+    ("Str8ts", Str8ts),
+    ("MultiSudoku", MultiSudoku),
+    ("InshiNoHeya", InshiNoHeya),
+];
+
+/// The rules by their name after the game type
+pub const RULES: [(&str, Rule); 4] = [
+    ("DistinctCages", Rule::DistinctCages),
+    ("AntiKnight", Rule::AntiKnight),
+    ("AntiKing", Rule::AntiKing),
+    ("Hyper", Rule::Hyper),
+];
+
+/// The names of all sections of a puzzle file
+pub const SECTIONS: [&str; 15] = [
+    "Regions",
+    "Extra Regions",
+    "Grids",
+    "Inequalities",
+    "Adjacent",
+    "Sandwiches",
+    "Thermometers",
+    "Arrows",
+    "Parity",
+    "Givens",
+    "Cages",
+    "Clues",
+    "Black",
+    "Digits",
+    "Symbols",
+];
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum GameType {
     KenKen,
     Sudoku,
+    Futoshiki,
+    Skyscrapers,
+    Kakuro,
+    Str8ts,
+    MultiSudoku,
+    InshiNoHeya,
+}
+
+/// Optional rules of a puzzle, given after the game type in the second line
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum Rule {
+    /// no digit may be repeated within a KenKen cage
+    DistinctCages,
+    /// positions a knight's move apart must not hold equal digits
+    AntiKnight,
+    /// positions a king's move apart must not hold equal digits
+    AntiKing,
+    /// the four additional 3x3 regions of a Hyper Sudoku (Windoku) hold distinct digits
+    Hyper,
 }
 
-#[derive(Debug, Clone, Getters)]
+#[derive(Debug, Clone, PartialEq, Getters)]
 pub struct PuzzleAsString {
     game_type: GameType,
+    rules: Vec<Rule>,
     normal_group_direction:bool,
     description: String,
     puzzle_string: Vec<String>,
+    sections: Vec<(String, Vec<String>)>,
+    metadata: Vec<(String, String)>,
+}
+
+/// A puzzle in the JSON format, the counterpart of the text format of a puzzle file
+///  * the cages of a KenKen with result, operation and cells (row and column),
+///    a distinct cage corresponds to a leading '!'
+///  * the rows of the specification of all other puzzles, e.g. the givens of a Sudoku
+///  * the givens of a KenKen (section "Givens")
+///  * the other sections, e.g. "Regions" or "Inequalities", by name
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonPuzzle {
+    #[serde(default)]
+    description: String,
+    game_type: GameType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rules: Vec<Rule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dimension: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    cages: Vec<JsonCage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rows: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    givens: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    sections: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, String>,
+}
+
+/// A KenKen cage in the JSON format, the cells are given as row and column
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct JsonCage {
+    result: usize,
+    operation: char,
+    cells: Vec<(usize, usize)>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    distinct: bool,
+}
+
+/// A solution in the JSON format, only the rows are needed to check a solution
+#[derive(Debug, Deserialize)]
+struct JsonSolution {
+    rows: Vec<String>,
 }
 
 impl PuzzleAsString {
+    /// Creates a puzzle file from its parts, e.g. to save a puzzle (see Puzzle::to_puzzle_string)
+    pub fn new(
+        description: String,
+        metadata: Vec<(String, String)>,
+        game_type: GameType,
+        rules: Vec<Rule>,
+        normal_group_direction: bool,
+        puzzle_string: Vec<String>,
+        sections: Vec<(String, Vec<String>)>,
+    ) -> Self {
+        PuzzleAsString {
+            game_type,
+            rules,
+            normal_group_direction,
+            description,
+            puzzle_string,
+            sections,
+            metadata,
+        }
+    }
+
     pub fn new_from_raw_string(raw_puzzle_string: String, normal_group_direction:bool) -> Result<Self, String> {
+        //report all problems of the text format at once with their line and column
+        let diagnostics = get_diagnostics(&raw_puzzle_string);
+        if !diagnostics.is_empty() {
+            let problems: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+            return Err(format!(
+                "Found {} problem(s) in the puzzle:\n{}",
+                problems.len(),
+                problems.join("\n")
+            ));
+        }
+
         let mut puzzle_string: Vec<String> = raw_puzzle_string
             .split('\n')
             .map(|c| c.trim().to_string())
@@ -28,40 +173,838 @@ impl PuzzleAsString {
         //first line of the file is the description
         let description = puzzle_string.remove(0);
 
-        //second line is the game type
-        let game_type: GameType = match &*puzzle_string.remove(0) {
-            "KenKen" => KenKen,
-            "Sudoku" => Sudoku,
-            other_type => return Err(format!("No valid Puzzle Type '{}'", other_type)),
+        //optional header lines "key: value" between description and game type, e.g. "author: ...",
+        //the keys are kept as written in their order, but compared case insensitive
+        let mut metadata: Vec<(String, String)> = Vec::new();
+        let mut header_rules: Vec<String> = Vec::new();
+        while let Some((key, value)) = puzzle_string.first().and_then(|line| get_header(line)) {
+            if key.eq_ignore_ascii_case("rules") {
+                //rule flags in the header are added to the rules after the game type
+                header_rules.extend(value.split_whitespace().map(|rule| rule.to_string()));
+            } else if metadata.iter().any(|(known_key, _)| known_key.eq_ignore_ascii_case(&key)) {
+                return Err(format!("Header '{}' is given more than once", key));
+            } else {
+                metadata.push((key, value));
+            }
+            puzzle_string.remove(0);
+        }
+
+        //next line is the game type, optionally followed by rules
+        if puzzle_string.is_empty() {
+            return Err("No game type found".to_string());
+        }
+        let type_line = puzzle_string.remove(0);
+        let mut type_tokens = type_line.split_whitespace();
+        let type_name = type_tokens.next().unwrap_or("");
+        let game_type: GameType = match GAME_TYPES.iter().find(|(name, _)| *name == type_name) {
+            Some(&(_, game_type)) => game_type,
+            None => return Err(format!("No valid Puzzle Type '{}'", type_name)),
         };
+        let mut rules: Vec<Rule> = Vec::new();
+        for rule_name in type_tokens.chain(header_rules.iter().map(|rule| rule.as_str())) {
+            rules.push(match RULES.iter().find(|(name, _)| *name == rule_name) {
+                Some(&(_, rule)) => rule,
+                None => return Err(format!("No valid Rule '{}'", rule_name)),
+            });
+        }
+
+        //the remaining lines are the puzzle specification, optionally followed by
+        //named sections, each starting with a line "[name]"
+        let mut sections: Vec<(String, Vec<String>)> = Vec::new();
+        let mut section_start = puzzle_string.len();
+        for (index, line) in puzzle_string.iter().enumerate() {
+            if line.starts_with('[') && line.ends_with(']') {
+                if sections.is_empty() {
+                    section_start = index;
+                }
+                sections.push((line[1..line.len() - 1].trim().to_string(), Vec::new()));
+            } else if let Some((_, section_lines)) = sections.last_mut() {
+                section_lines.push(line.clone());
+            }
+        }
+        puzzle_string.truncate(section_start);
+
+        //a KenKen might be given as a cage map with the clues of the cages in the section "Cages"
+        if game_type == KenKen {
+            if let Some(index) = sections.iter().position(|(name, _)| name == "Cages") {
+                let (_, cage_clues) = sections.remove(index);
+                puzzle_string = get_cage_lines_from_map(&puzzle_string, &cage_clues)?;
+            }
+        }
 
         Ok(PuzzleAsString {
             game_type,
+            rules,
             normal_group_direction,
             description,
             puzzle_string,
+            sections,
+            metadata,
         })
     }
 
-    pub fn new_from_file(file_name: &str) -> Result<Self, String> {
-        let raw_puzzle_string = match fs::read_to_string(file_name.trim()) {
-            Ok(raw_puzzle) => raw_puzzle,
-            Err(e) => {
+    /// Creates a puzzle from a JSON object in the format of JsonPuzzle
+    pub fn new_from_json(json: &str, normal_group_direction: bool) -> Result<Self, String> {
+        let json_puzzle: JsonPuzzle = serde_json::from_str(json)
+            .map_err(|e| format!("No valid JSON puzzle found: {}", e))?;
+        PuzzleAsString::new_from_json_puzzle(json_puzzle, normal_group_direction)
+    }
+
+    /// Converts a JSON puzzle to the text format and loads it from there,
+    /// so both formats share the same checks
+    fn new_from_json_puzzle(
+        json_puzzle: JsonPuzzle,
+        normal_group_direction: bool,
+    ) -> Result<Self, String> {
+        let description = match json_puzzle.description.trim() {
+            "" => format!("{:?} puzzle", json_puzzle.game_type),
+            description => description.to_string(),
+        };
+        let type_line: String = std::iter::once(format!("{:?}", json_puzzle.game_type))
+            .chain(json_puzzle.rules.iter().map(|rule| format!("{:?}", rule)))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let mut lines: Vec<String> = vec![description, type_line];
+        for cage in json_puzzle.cages.iter() {
+            let cells: Vec<String> = cage
+                .cells
+                .iter()
+                .map(|&(row, column)| format!("{}{}", row, column))
+                .collect();
+            lines.push(format!(
+                "{}{}{}{}",
+                if cage.distinct { "!" } else { "" },
+                cage.result,
+                cage.operation,
+                cells.join(".")
+            ));
+        }
+        lines.extend(json_puzzle.rows);
+        if !json_puzzle.givens.is_empty() {
+            lines.push("[Givens]".to_string());
+            lines.extend(json_puzzle.givens);
+        }
+        for (name, section_lines) in json_puzzle.sections {
+            lines.push(format!("[{}]", name));
+            lines.extend(section_lines);
+        }
+
+        let mut puzzle = PuzzleAsString::new_from_raw_string(lines.join("\n"), normal_group_direction)?;
+        if let Some(dimension) = json_puzzle.dimension {
+            if puzzle.get_dimension()? != dimension {
                 return Err(format!(
-                    "Error reading file. Error message:\n{}",
-                    e.to_string()
-                ))
+                    "Dimension {} doesn't match the puzzle of dimension {}",
+                    dimension,
+                    puzzle.get_dimension()?
+                ));
             }
+        }
+        puzzle.metadata = json_puzzle.metadata.into_iter().collect();
+
+        Ok(puzzle)
+    }
+
+    /// Converts the puzzle to a JSON object in the format of JsonPuzzle
+    pub fn to_json(&self) -> Result<String, String> {
+        let mut json_puzzle = JsonPuzzle {
+            description: self.description.clone(),
+            game_type: self.game_type,
+            rules: self.rules.clone(),
+            dimension: self.get_dimension().ok(),
+            cages: Vec::new(),
+            rows: Vec::new(),
+            givens: Vec::new(),
+            sections: BTreeMap::new(),
+            metadata: self.metadata.iter().cloned().collect(),
         };
 
+        if self.game_type == KenKen {
+            for cage_line in self.puzzle_string.iter() {
+                json_puzzle.cages.push(get_json_cage(cage_line)?);
+            }
+        } else {
+            json_puzzle.rows = self.puzzle_string.clone();
+        }
+        for (name, section_lines) in self.sections.iter() {
+            if self.game_type == KenKen && name == "Givens" {
+                json_puzzle.givens = section_lines.clone();
+            } else {
+                json_puzzle.sections.insert(name.clone(), section_lines.clone());
+            }
+        }
+
+        serde_json::to_string(&json_puzzle).map_err(|e| e.to_string())
+    }
+
+    /// Loads a single puzzle from a file, the file name "-" reads the puzzle from stdin
+    pub fn new_from_file(file_name: &str) -> Result<Self, String> {
+        PuzzleAsString::new_from_file_content(read_input(file_name)?)
+    }
+
+    /// Loads a single puzzle from the content of a file, i.e. a puzzle in the text format,
+    /// a JSON puzzle object or a saved HTML page with a KenKen
+    fn new_from_file_content(raw_puzzle_string: String) -> Result<Self, String> {
+        //a JSON file holds a single puzzle object, an HTML file a saved page with a KenKen
+        match raw_puzzle_string.trim_start().chars().next() {
+            Some('{') => return PuzzleAsString::new_from_json(&raw_puzzle_string, true),
+            Some('<') => {
+                let kenken_string = get_kenken_from_html(&raw_puzzle_string)?;
+                return PuzzleAsString::new_from_raw_string(kenken_string, true);
+            }
+            _ => (),
+        }
+
         PuzzleAsString::new_from_raw_string(raw_puzzle_string,true)
     }
 
+    /// Converts a KenKen to the cage map format, i.e. one line per row with one character
+    /// per position naming the cage, followed by the section "Cages" with the clue of each cage
+    pub fn to_cage_map(&self) -> Result<String, String> {
+        if self.game_type != KenKen {
+            return Err(format!("Only a KenKen has a cage map, found {:?}", self.game_type));
+        }
+        let dimension = self.get_dimension()?;
+        if self.puzzle_string.len() > CAGE_IDS.len() {
+            return Err(format!(
+                "A cage map supports at most {} cages, found {}",
+                CAGE_IDS.len(),
+                self.puzzle_string.len()
+            ));
+        }
+
+        let mut cage_map: Vec<Vec<char>> = vec![vec!['-'; dimension]; dimension];
+        let mut cage_clues: Vec<String> = Vec::new();
+        for (cage_line, id) in self.puzzle_string.iter().zip(CAGE_IDS.chars()) {
+            let cage = get_json_cage(cage_line)?;
+            cage.cells.iter().for_each(|&(row, column)| cage_map[row][column] = id);
+            cage_clues.push(format!(
+                "{} {}{}{}",
+                id,
+                if cage.distinct { "!" } else { "" },
+                cage.result,
+                cage.operation
+            ));
+        }
+
+        let rows: Vec<String> = cage_map.into_iter().map(|row| row.into_iter().collect()).collect();
+        Ok(self.format_raw_string(&rows, &[("Cages".to_string(), cage_clues)]))
+    }
+
+    /// Converts the puzzle to the text format of a puzzle file, i.e. the description,
+    /// the metadata as header lines, the game type with the rules, the given lines of the puzzle
+    /// and the sections, the given additional sections precede the sections of the puzzle
+    pub fn format_raw_string(
+        &self,
+        puzzle_lines: &[String],
+        additional_sections: &[(String, Vec<String>)],
+    ) -> String {
+        let type_line: String = std::iter::once(format!("{:?}", self.game_type))
+            .chain(self.rules.iter().map(|rule| format!("{:?}", rule)))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let mut lines: Vec<String> = vec![self.description.clone()];
+        lines.extend(self.metadata.iter().map(|(key, value)| format!("{}: {}", key, value)));
+        lines.push(type_line);
+        lines.extend(puzzle_lines.iter().cloned());
+        for (name, section_lines) in additional_sections.iter().chain(self.sections.iter()) {
+            lines.push(format!("[{}]", name));
+            lines.extend(section_lines.iter().cloned());
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    /// Creates a Sudoku from the one line format, i.e. the 81 positions row by row
+    /// with the digits 1 to 9 and '.' or '0' for open positions,
+    /// optionally followed by a description separated by whitespace.
+    /// Without a description, the line itself is the description.
+    pub fn new_from_sudoku_line(line: &str, normal_group_direction: bool) -> Result<Self, String> {
+        let line = line.trim();
+        let (positions, description) = match line.split_once(char::is_whitespace) {
+            Some((positions, description)) => (positions, description.trim()),
+            None => (line, line),
+        };
+        if !is_sudoku_line(positions) {
+            return Err(format!("No valid one line Sudoku found: {}", line));
+        }
+
+        let rows: Vec<String> = positions
+            .chars()
+            .map(|c| if c == '.' || c == '0' { '-' } else { c })
+            .collect::<Vec<char>>()
+            .chunks(9)
+            .map(|row| row.iter().collect())
+            .collect();
+
+        PuzzleAsString::new_from_raw_string(
+            format!("{}\nSudoku\n{}", description, rows.join("\n")),
+            normal_group_direction,
+        )
+    }
+
+    /// Creates a KenKen from a game ID of the puzzle "Keen" of Simon Tatham's Portable
+    /// Puzzle Collection, e.g. "4:ab_4a3_2ba2_,s1a8m6s2m16a6", optionally followed by a description.
+    ///
+    /// The ID holds the parameters (the size, e.g. "6dn" for a 6 x 6 puzzle of normal difficulty),
+    /// ':', the borders between the cages and ',' followed by the clue of each cage
+    /// in the order of the first position of the cages. The parameters are kept in the header
+    /// "keen params", cages of a single position get the operation 'c'.
+    pub fn new_from_keen_id(line: &str, normal_group_direction: bool) -> Result<Self, String> {
+        let line = line.trim();
+        let (game_id, description) = match line.split_once(char::is_whitespace) {
+            Some((game_id, description)) => (game_id, description.trim()),
+            None => (line, line),
+        };
+        if !is_keen_id(game_id) {
+            return Err(format!("No valid Keen game ID found: {}", game_id));
+        }
+        let (params, game_description) = game_id.split_once(':').unwrap_or_default();
+        let (borders, clues) = game_description.split_once(',').unwrap_or_default();
+        let dimension: usize = params
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>()
+            .parse()
+            .unwrap_or(0);
+        if !(3..=9).contains(&dimension) {
+            return Err(format!("Keen size must be 3 to 9, found {}", params));
+        }
+
+        let cages = get_keen_cages(borders, dimension)?;
+        let mut cage_lines: Vec<String> = Vec::new();
+        let mut clue_chars = clues.chars().peekable();
+        while let Some(operation) = clue_chars.next() {
+            let mut result = String::new();
+            while let Some(digit) = clue_chars.next_if(|c| c.is_ascii_digit()) {
+                result.push(digit);
+            }
+            let cage = cages.get(cage_lines.len()).ok_or("Keen ID has more clues than cages")?;
+            let operation = match (operation, cage.len()) {
+                ('a', 1) => 'c',
+                ('a', _) => '+',
+                ('s', _) => '-',
+                ('m', _) => '*',
+                ('d', _) => ':',
+                _ => return Err(format!("No valid Keen clue operation '{}'", operation)),
+            };
+            if result.is_empty() {
+                return Err(format!("Keen clue '{}' without result", operation));
+            }
+            let positions: Vec<String> = cage
+                .iter()
+                .map(|cell| format!("{}{}", cell / dimension, cell % dimension))
+                .collect();
+            cage_lines.push(format!("{}{}{}", result, operation, positions.join(".")));
+        }
+        if cage_lines.len() != cages.len() {
+            return Err(format!(
+                "Keen ID has {} cages, but {} clues",
+                cages.len(),
+                cage_lines.len()
+            ));
+        }
+
+        PuzzleAsString::new_from_raw_string(
+            format!(
+                "{}\nkeen params: {}\nKenKen\n{}",
+                description,
+                params,
+                cage_lines.join("\n")
+            ),
+            normal_group_direction,
+        )
+    }
+
+    /// Converts a KenKen to a game ID of the puzzle "Keen" (see new_from_keen_id),
+    /// which has no distinct cages, givens, other digits or additional sections
+    pub fn to_keen_id(&self) -> Result<String, String> {
+        if self.game_type != KenKen {
+            return Err(format!("Only a KenKen has a Keen ID, found {:?}", self.game_type));
+        }
+        if !self.rules.is_empty() || !self.sections.is_empty() {
+            return Err("Keen doesn't support rules or sections of a KenKen".to_string());
+        }
+        let cage_problems = self.get_cage_problems();
+        if !cage_problems.is_empty() {
+            return Err(cage_problems.join("\n"));
+        }
+        let dimension = self.get_dimension()?;
+
+        //the cage of each position and the clue of each cage
+        let mut position_cages: Vec<usize> = vec![0; dimension * dimension];
+        let mut clues: Vec<(usize, String)> = Vec::new();
+        for (index, cage_line) in self.puzzle_string.iter().enumerate() {
+            let cage = get_json_cage(cage_line)?;
+            if cage.distinct {
+                return Err(format!("Keen doesn't support distinct cages: {}", cage_line));
+            }
+            let cells: Vec<usize> =
+                cage.cells.iter().map(|&(row, column)| row * dimension + column).collect();
+            cells.iter().for_each(|&cell| position_cages[cell] = index);
+            let operation = match cage.operation {
+                '+' | 'c' => 'a',
+                '-' => 's',
+                '*' => 'm',
+                _ => 'd',
+            };
+            let first_cell = cells.iter().min().copied().unwrap_or_default();
+            clues.push((first_cell, format!("{}{}", operation, cage.result)));
+        }
+        clues.sort();
+
+        //count the inner lines without border between the borders, see get_keen_cages
+        let mut borders: Vec<char> = Vec::new();
+        let mut run = 0;
+        let line_count = 2 * dimension * (dimension - 1);
+        for line in 0..=line_count {
+            let is_border = line == line_count || {
+                let (cell, neighbour) = get_keen_line_cells(line, dimension);
+                position_cages[cell] != position_cages[neighbour]
+            };
+            if is_border {
+                while run > 25 {
+                    borders.push('z');
+                    run -= 26;
+                }
+                borders.push(if run == 0 { '_' } else { (b'a' + run as u8 - 1) as char });
+                run = 0;
+            } else {
+                run += 1;
+            }
+        }
+
+        //runs of the same character are given by the character and the count
+        let borders_string: String = borders
+            .chunk_by(|c, other| c == other)
+            .map(|chunk| match chunk.len() {
+                1 => chunk[0].to_string(),
+                count => format!("{}{}", chunk[0], count),
+            })
+            .collect();
+        let params = match self.get_metadata("keen params") {
+            Some(params) if params.starts_with(&dimension.to_string()) => params.clone(),
+            _ => dimension.to_string(),
+        };
+        let clues_string: String = clues.into_iter().map(|(_, clue)| clue).collect();
+
+        Ok(format!("{}:{},{}", params, borders_string, clues_string))
+    }
+
+    /// Loads all puzzles of a file, i.e. either a single puzzle, a batch of Sudokus
+    /// in the one line format (one Sudoku per line, lines starting with '#' are comments)
+    /// or JSON puzzles (a single object or an array), the file name "-" reads the puzzles from stdin
+    pub fn new_list_from_file(file_name: &str) -> Result<Vec<Self>, String> {
+        let raw_puzzle_string = read_input(file_name)?;
+
+        //a JSON file holds a single puzzle or an array of puzzles
+        match raw_puzzle_string.trim_start().chars().next() {
+            Some('{') => return Ok(vec![PuzzleAsString::new_from_json(&raw_puzzle_string, true)?]),
+            Some('[') => {
+                let json_puzzles: Vec<JsonPuzzle> = serde_json::from_str(&raw_puzzle_string)
+                    .map_err(|e| format!("No valid JSON puzzles found: {}", e))?;
+                return json_puzzles
+                    .into_iter()
+                    .map(|json_puzzle| PuzzleAsString::new_from_json_puzzle(json_puzzle, true))
+                    .collect();
+            }
+            Some('<') => return Ok(vec![PuzzleAsString::new_from_file_content(raw_puzzle_string)?]),
+            _ => (),
+        }
+
+        let lines: Vec<(usize, &str)> = raw_puzzle_string
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        let is_batch = lines.first().is_some_and(|(_, line)| {
+            let first_token = line.split_whitespace().next().unwrap_or("");
+            is_sudoku_line(first_token) || is_keen_id(first_token)
+        });
+        if !is_batch {
+            return Ok(vec![PuzzleAsString::new_from_raw_string(raw_puzzle_string, true)?]);
+        }
+
+        //each line of a batch is either a Sudoku in the one line format or a Keen game ID
+        lines
+            .into_iter()
+            .map(|(line_number, line)| {
+                let puzzle = if is_keen_id(line.split_whitespace().next().unwrap_or("")) {
+                    PuzzleAsString::new_from_keen_id(line, true)
+                } else {
+                    PuzzleAsString::new_from_sudoku_line(line, true)
+                };
+                puzzle.map_err(|e| format!("Line {}: {}", line_number, e))
+            })
+            .collect()
+    }
+
+    pub fn has_rule(&self, rule: Rule) -> bool {
+        self.rules.contains(&rule)
+    }
+
+    /// Retrieves the value of the header with the given key (case insensitive),
+    /// None if the puzzle file has no such header
+    pub fn get_metadata(&self, key: &str) -> Option<&String> {
+        self.metadata
+            .iter()
+            .find(|(header_key, _)| header_key.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    /// Retrieves the expected solution of the header "expected solution" (or "solution"),
+    /// i.e. the rows of the solution separated by ','
+    pub fn get_expected_solution(&self) -> Option<&String> {
+        self.get_metadata("expected solution").or_else(|| self.get_metadata("solution"))
+    }
+
+    /// Converts the puzzle to the text of a puzzle file, which is loaded to an equal puzzle,
+    /// a KenKen given as cage map is written in the coordinate format
+    pub fn to_raw_string(&self) -> String {
+        self.format_raw_string(&self.puzzle_string, &[])
+    }
+
+    /// Retrieves the lines of the section with the given name,
+    /// None if the puzzle file has no such section
+    pub fn get_section(&self, name: &str) -> Option<&Vec<String>> {
+        self.sections
+            .iter()
+            .find(|(section_name, _)| section_name == name)
+            .map(|(_, section_lines)| section_lines)
+    }
+
+    /// Retrieves the regions of a Sudoku, i.e. the areas of 9 positions, which must hold
+    /// disjunctive digits.
+    ///
+    /// Without a section "Regions" the regions are the nine 3x3 quadrants.
+    /// Otherwise the section holds a region map with one line per row and one character
+    /// per position naming the region of the position ('.' might be used as separator).
+    /// The map must define exactly 9 regions of 9 orthogonally connected positions each.
+    pub fn get_regions(&self) -> Result<Vec<Vec<usize>>, String> {
+        let region_map = match self.get_section("Regions") {
+            Some(region_map) => region_map,
+            None => {
+                return Ok((0..9)
+                    .map(|quadrant| {
+                        (0..9)
+                            .map(|i| (3 * (quadrant / 3) + (i / 3)) * 10 + (3 * (quadrant % 3) + (i % 3)))
+                            .collect()
+                    })
+                    .collect())
+            }
+        };
+
+        let rows: Vec<Vec<char>> = region_map
+            .iter()
+            .map(|line| line.chars().filter(|&c| c != '.').collect())
+            .collect();
+        if rows.len() != 9 || rows.iter().any(|row| row.len() != 9) {
+            return Err(format!(
+                "Region map must have 9 rows with 9 positions each.\n{:?}",
+                region_map
+            ));
+        }
+
+        //collect the positions per region id in order of appearance
+        let mut region_ids: Vec<char> = Vec::new();
+        let mut regions: Vec<Vec<usize>> = Vec::new();
+        for (row, row_ids) in rows.iter().enumerate() {
+            for (column, id) in row_ids.iter().enumerate() {
+                match region_ids.iter().position(|r| r == id) {
+                    Some(index) => regions[index].push(row * 10 + column),
+                    None => {
+                        region_ids.push(*id);
+                        regions.push(vec![row * 10 + column]);
+                    }
+                }
+            }
+        }
+
+        if regions.len() != 9 {
+            return Err(format!(
+                "Region map must define exactly 9 regions, found {}: {:?}",
+                regions.len(),
+                region_ids
+            ));
+        }
+        for (id, region) in region_ids.iter().zip(regions.iter()) {
+            if region.len() != 9 {
+                return Err(format!(
+                    "Region '{}' must have 9 positions, found {}",
+                    id,
+                    region.len()
+                ));
+            }
+            if !is_connected(region) {
+                return Err(format!("Region '{}' is not connected: {:?}", id, region));
+            }
+        }
+
+        Ok(regions)
+    }
+
+    /// Retrieves the additional regions of distinct digits of a Sudoku or KenKen,
+    /// i.e. the four 3x3 regions of the rule "Hyper" and the regions of the section "Extra Regions".
+    ///
+    /// Each line of the section holds the positions of one region separated by '.',
+    /// e.g. "00.11.22.33" for the diagonal of a 4x4 KenKen.
+    /// A region holds at most as many positions as the puzzle has digits.
+    pub fn get_extra_regions(&self) -> Result<Vec<Vec<usize>>, String> {
+        let dimension = self.get_dimension()?;
+        let mut extra_regions: Vec<Vec<usize>> = Vec::new();
+
+        if self.has_rule(Rule::Hyper) {
+            if dimension != 9 {
+                return Err(format!("Rule Hyper requires dimension 9, found {}", dimension));
+            }
+            for (row, column) in [(1, 1), (1, 5), (5, 1), (5, 5)] {
+                extra_regions
+                    .push((0..9).map(|i| (row + i / 3) * 10 + column + i % 3).collect());
+            }
+        }
+
+        for region_line in self.get_section("Extra Regions").into_iter().flatten() {
+            let mut region: Vec<usize> = Vec::new();
+            for position_string in region_line.split('.') {
+                match position_string.parse::<usize>() {
+                    Ok(position)
+                        if position_string.len() == 2
+                            && position / 10 < dimension
+                            && position % 10 < dimension
+                            && !region.contains(&position) =>
+                    {
+                        region.push(position)
+                    }
+                    _ => return Err(format!("No valid extra region found: {}", region_line)),
+                }
+            }
+            if region.len() > dimension {
+                return Err(format!(
+                    "Extra region must have at most {} positions: {}",
+                    dimension, region_line
+                ));
+            }
+            extra_regions.push(region);
+        }
+
+        Ok(extra_regions)
+    }
+
+    /// Retrieves the offsets (row and column) of the 9x9 grids of a multi grid Sudoku
+    /// from the section "Grids", e.g. "0 0", "6 6" for a twin Sudoku sharing one region.
+    ///
+    /// Each line of the section holds the row and the column of the upper left corner of a grid
+    /// within the rows of the puzzle.
+    pub fn get_grid_offsets(&self) -> Result<Vec<(usize, usize)>, String> {
+        let grid_lines = match self.get_section("Grids") {
+            Some(grid_lines) if !grid_lines.is_empty() => grid_lines,
+            _ => return Err("No section [Grids] with the grid offsets found".to_string()),
+        };
+
+        let mut grid_offsets: Vec<(usize, usize)> = Vec::new();
+        for grid_line in grid_lines {
+            let offsets: Vec<Result<usize, _>> =
+                grid_line.split_whitespace().map(|offset| offset.parse::<usize>()).collect();
+            match offsets[..] {
+                [Ok(row), Ok(column)] => grid_offsets.push((row, column)),
+                _ => return Err(format!("No valid grid offset found: {}", grid_line)),
+            }
+        }
+
+        Ok(grid_offsets)
+    }
+
+    /// Retrieves the black positions of a Str8ts from the section "Black".
+    ///
+    /// The section holds a map with one line per row and one character per position,
+    /// black positions are marked by '#', white positions by '-'.
+    pub fn get_black_positions(&self) -> Result<Vec<usize>, String> {
+        let dimension = self.get_dimension()?;
+        let black_map = match self.get_section("Black") {
+            Some(black_map) => black_map,
+            None => return Err("No section [Black] with the black positions found".to_string()),
+        };
+        if black_map.len() != dimension
+            || black_map.iter().any(|row| row.chars().count() != dimension)
+        {
+            return Err(format!(
+                "Black map must have {} rows with {} positions each.\n{:?}",
+                dimension, dimension, black_map
+            ));
+        }
+
+        let mut black_positions: Vec<usize> = Vec::new();
+        for (row, row_string) in black_map.iter().enumerate() {
+            for (column, c) in row_string.chars().enumerate() {
+                match c {
+                    '#' => black_positions.push(row * 10 + column),
+                    '-' => (),
+                    _ => return Err(format!("No valid row of the black map: {}", row_string)),
+                }
+            }
+        }
+
+        Ok(black_positions)
+    }
+
+    /// Retrieves the shaded positions of the section "Parity" with the remainder (modulo 2)
+    /// of the digits allowed in each of them, i.e. 0 for even and 1 for odd digits.
+    ///
+    /// The section holds a map with one line per row and one character per position,
+    /// even positions are marked by 'e', odd positions by 'o' and all others by '-'.
+    /// Without a section "Parity" no position is shaded.
+    pub fn get_parity_positions(&self) -> Result<Vec<(usize, usize)>, String> {
+        let dimension = self.get_dimension()?;
+        let parity_map = match self.get_section("Parity") {
+            Some(parity_map) => parity_map,
+            None => return Ok(Vec::new()),
+        };
+        if parity_map.len() != dimension
+            || parity_map.iter().any(|row| row.chars().count() != dimension)
+        {
+            return Err(format!(
+                "Parity map must have {} rows with {} positions each.\n{:?}",
+                dimension, dimension, parity_map
+            ));
+        }
+
+        let mut parity_positions: Vec<(usize, usize)> = Vec::new();
+        for (row, row_string) in parity_map.iter().enumerate() {
+            for (column, c) in row_string.chars().enumerate() {
+                match c {
+                    'e' => parity_positions.push((row * 10 + column, 0)),
+                    'o' => parity_positions.push((row * 10 + column, 1)),
+                    '-' => (),
+                    _ => return Err(format!("No valid row of the parity map: {}", row_string)),
+                }
+            }
+        }
+
+        Ok(parity_positions)
+    }
+
+    /// Retrieves the digits of the puzzle, by default the digits 1 to dimension
+    /// (1 to 9 for Kakuro, whose runs are shorter than the dimension).
+    ///
+    /// The section "Digits" might define other digits, e.g. "0123" for a zero based 4x4 KenKen.
+    /// Its only line holds one digit (0 to 9) per character, the count of digits
+    /// must match the dimension (9 for Kakuro).
+    pub fn get_digits(&self) -> Result<Vec<usize>, String> {
+        let dimension = match self.game_type {
+            Kakuro => 9,
+            _ => self.get_dimension()?,
+        };
+        let digit_line = match self.get_section("Digits") {
+            Some(digit_lines) if !digit_lines.is_empty() => &digit_lines[0],
+            _ => return Ok((1..=dimension).collect()),
+        };
+
+        let mut digits: Vec<usize> = Vec::new();
+        for c in digit_line.chars() {
+            match c.to_digit(10) {
+                Some(digit) if !digits.contains(&(digit as usize)) => digits.push(digit as usize),
+                _ => return Err(format!("No valid digits found: {}", digit_line)),
+            }
+        }
+        if digits.len() != dimension {
+            return Err(format!(
+                "Count of digits {} doesn't match the dimension {}: {}",
+                digits.len(),
+                dimension,
+                digit_line
+            ));
+        }
+        digits.sort_unstable();
+
+        Ok(digits)
+    }
+
+    /// Retrieves the symbols representing the given digits in the puzzle file and the solution,
+    /// by default the digits themselves.
+    ///
+    /// The section "Symbols" might define other symbols, e.g. "ABCDEFGHI" for a Sudoku.
+    /// Its only line holds one symbol per digit (in ascending order of the digits),
+    /// '-' and '.' can't be used as symbols.
+    pub fn get_symbols(&self, digits: &[usize]) -> Result<Vec<char>, String> {
+        let symbol_line = match self.get_section("Symbols") {
+            Some(symbol_lines) if !symbol_lines.is_empty() => &symbol_lines[0],
+            _ => {
+                return Ok(digits
+                    .iter()
+                    .map(|&digit| char::from_digit(digit as u32, 10).unwrap_or('?'))
+                    .collect())
+            }
+        };
+
+        let symbols: Vec<char> = symbol_line.chars().collect();
+        let mut unique_symbols = symbols.clone();
+        unique_symbols.sort_unstable();
+        unique_symbols.dedup();
+        if symbols.len() != digits.len()
+            || unique_symbols.len() != symbols.len()
+            || symbols.iter().any(|&c| c == '-' || c == '.')
+        {
+            return Err(format!(
+                "Symbols must be {} distinct characters other than '-' and '.': {}",
+                digits.len(),
+                symbol_line
+            ));
+        }
+
+        Ok(symbols)
+    }
+
     pub fn get_dimension(&self) -> Result<usize, String> {
-        if self.game_type == Sudoku {
+        if self.game_type == Sudoku || self.game_type == MultiSudoku {
             return Ok(9);
         };
 
+        //a Futoshiki, Kakuro or Str8ts has one line per row with one character per position
+        if [Futoshiki, Kakuro, Str8ts].contains(&self.game_type) {
+            let dim = self.puzzle_string.len();
+            if (3..=9).contains(&dim)
+                && self.puzzle_string.iter().all(|row| row.chars().count() == dim)
+            {
+                return Ok(dim);
+            }
+            return Err(format!(
+                "Dimension can't be determined. {:?} must have 3 to 9 rows \
+                    with one position per column each.\n{:?}",
+                self.game_type, self.puzzle_string
+            ));
+        };
+
+        //an Inshi no heya has one line of rooms per row
+        if self.game_type == InshiNoHeya {
+            let dim = self.puzzle_string.len();
+            if (3..=9).contains(&dim) {
+                return Ok(dim);
+            }
+            return Err(format!(
+                "Dimension can't be determined. InshiNoHeya must have 3 to 9 rows.\n{:?}",
+                self.puzzle_string
+            ));
+        };
+
+        //a Skyscrapers puzzle has 4 lines of clues (top, bottom, left, right)
+        //with one character per row or column
+        if self.game_type == Skyscrapers {
+            let dim = self.puzzle_string.first().map_or(0, |clues| clues.chars().count());
+            if self.puzzle_string.len() == 4
+                && (3..=9).contains(&dim)
+                && self.puzzle_string.iter().all(|clues| clues.chars().count() == dim)
+            {
+                return Ok(dim);
+            }
+            return Err(format!(
+                "Dimension can't be determined. Skyscrapers must have 4 lines of clues \
+                    with one clue per row or column each.\n{:?}",
+                self.puzzle_string
+            ));
+        };
+
         //get all positions from the puzzle string into a vec of positions
         let mut positions_list: Vec<usize> = self
             .puzzle_string
@@ -117,15 +1060,391 @@ impl PuzzleAsString {
             positions_count, position_count_dedup, positions_list
         ))
     }
+
+    /// Checks the cages of a KenKen as a whole and reports all problems per cage, i.e.
+    /// positions outside of the grid, cages with positions not orthogonally connected,
+    /// positions belonging to several cages, positions without a cage and
+    /// subtraction, division or constant cages with the wrong count of positions.
+    ///
+    /// The grid has the dimension of the header "dimension", if declared,
+    /// otherwise the largest row or column of the cages. Other puzzles have no cages to check.
+    pub fn get_cage_problems(&self) -> Vec<String> {
+        if self.game_type != KenKen {
+            return Vec::new();
+        }
+        let mut problems: Vec<String> = Vec::new();
+        let mut cages: Vec<(usize, &String, JsonCage)> = Vec::new();
+        for (index, cage_line) in self.puzzle_string.iter().enumerate() {
+            match get_json_cage(cage_line) {
+                Ok(cage) => cages.push((index + 1, cage_line, cage)),
+                Err(e) => problems.push(format!("Cage {}: {}", index + 1, e)),
+            }
+        }
+
+        let dimension = match self.get_metadata("dimension") {
+            Some(declared) => match declared.parse::<usize>() {
+                Ok(dimension) if (3..=9).contains(&dimension) => dimension,
+                _ => {
+                    problems.push(format!("Declared dimension '{}' must be 3 to 9", declared));
+                    return problems;
+                }
+            },
+            None => cages
+                .iter()
+                .flat_map(|(_, _, cage)| cage.cells.iter())
+                .map(|&(row, column)| row.max(column) + 1)
+                .max()
+                .unwrap_or(0),
+        };
+        if !(3..=9).contains(&dimension) {
+            problems.push(format!(
+                "The cages span {} rows and columns, a KenKen has 3 to 9",
+                dimension
+            ));
+        }
+
+        //the number of the cage of each position to find overlaps and gaps
+        let mut position_cages: BTreeMap<usize, usize> = BTreeMap::new();
+        for (number, cage_line, cage) in cages.iter() {
+            let cage_name = format!("Cage {} '{}'", number, cage_line);
+            let positions: Vec<usize> =
+                cage.cells.iter().map(|&(row, column)| row * 10 + column).collect();
+
+            let required_count = match cage.operation {
+                '+' | '*' => None,
+                '-' | ':' => Some(2),
+                'c' => Some(1),
+                operation => {
+                    problems.push(format!("{}: no valid operation '{}'", cage_name, operation));
+                    None
+                }
+            };
+            if let Some(count) = required_count.filter(|&count| count != positions.len()) {
+                problems.push(format!(
+                    "{}: operation '{}' needs {} position(s), found {}",
+                    cage_name,
+                    cage.operation,
+                    count,
+                    positions.len()
+                ));
+            }
+
+            let outside: Vec<String> = cage
+                .cells
+                .iter()
+                .filter(|&&(row, column)| row >= dimension || column >= dimension)
+                .map(|&(row, column)| format!("{}{}", row, column))
+                .collect();
+            if !outside.is_empty() {
+                problems.push(format!(
+                    "{}: positions {} are outside of the {} x {} grid",
+                    cage_name,
+                    outside.join(", "),
+                    dimension,
+                    dimension
+                ));
+            }
+
+            if !is_connected(&positions) {
+                problems.push(format!("{}: positions are not connected", cage_name));
+            }
+
+            for &position in positions.iter() {
+                match position_cages.insert(position, *number) {
+                    Some(other) if other == *number => problems.push(format!(
+                        "{}: position {:02} is given more than once",
+                        cage_name, position
+                    )),
+                    Some(other) => problems.push(format!(
+                        "{}: position {:02} is already part of cage {}",
+                        cage_name, position, other
+                    )),
+                    None => (),
+                }
+            }
+        }
+
+        let gaps: Vec<String> = (0..dimension)
+            .flat_map(|row| (0..dimension).map(move |column| row * 10 + column))
+            .filter(|position| !position_cages.contains_key(position))
+            .map(|position| format!("{:02}", position))
+            .collect();
+        if !gaps.is_empty() && !cages.is_empty() {
+            problems.push(format!("Positions {} aren't part of any cage", gaps.join(", ")));
+        }
+
+        problems
+    }
+}
+
+/// Reads the content of a puzzle or solution file, the file name "-" reads stdin,
+/// so puzzles can be piped from other programs, e.g. from the generator
+pub fn read_input(file_name: &str) -> Result<String, String> {
+    let content = if file_name.trim() == "-" {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content).map(|_| content)
+    } else {
+        fs::read_to_string(file_name.trim())
+    };
+    content.map_err(|e| format!("Error reading file. Error message:\n{}", e))
+}
+
+/// Checks if the given positions form one orthogonally connected area,
+/// i.e. each position can be reached from each other position by steps
+/// to the upper, lower, left or right neighbour within the area
+pub fn is_connected(positions: &[usize]) -> bool {
+    if positions.is_empty() {
+        return false;
+    }
+
+    let mut reached: Vec<usize> = vec![positions[0]];
+    let mut index = 0;
+    while index < reached.len() {
+        let position = reached[index];
+        for &candidate in positions {
+            let is_neighbour = (candidate / 10 == position / 10
+                && (candidate % 10 + 1 == position % 10 || position % 10 + 1 == candidate % 10))
+                || (candidate % 10 == position % 10
+                    && (candidate / 10 + 1 == position / 10 || position / 10 + 1 == candidate / 10));
+            if is_neighbour && !reached.contains(&candidate) {
+                reached.push(candidate);
+            }
+        }
+        index += 1;
+    }
+
+    reached.len() == positions.len()
 }
 
 impl fmt::Display for PuzzleAsString {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let display: String = format!("{}\nType: {:?}", self.description, self.game_type);
+        let mut display: String = format!("{}\nType: {:?}", self.description, self.game_type);
+        if !self.rules.is_empty() {
+            display = format!("{}\nRules: {:?}", display, self.rules);
+        }
+        for (key, value) in self.metadata.iter() {
+            display = format!("{}\n{}: {}", display, key, value);
+        }
         write!(f, "{}", display)
     }
 }
 
+/// Splits a header line "key: value" into the key (as written) and the value,
+/// a key consists of letters, digits, '_', '-' and spaces, e.g. "expected solution"
+pub fn get_header(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim();
+    if key.is_empty()
+        || !key.starts_with(|c: char| c.is_ascii_alphabetic())
+        || !key.chars().all(|c| c.is_ascii_alphanumeric() || " _-".contains(c))
+    {
+        return None;
+    }
+    Some((key.to_string(), value.trim().to_string()))
+}
+
+/// The characters naming the cages of a cage map created from a KenKen
+const CAGE_IDS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Converts the rows of a cage map with the clues of the cages, e.g. "A 288*" or "B !12+",
+/// to the lines of a KenKen with the cages given by their positions, e.g. "288*77.78.87.88".
+/// The positions named by the same character must be orthogonally connected.
+fn get_cage_lines_from_map(
+    cage_map: &[String],
+    cage_clues: &[String],
+) -> Result<Vec<String>, String> {
+    let dimension = cage_map.len();
+    if !(3..=9).contains(&dimension) || cage_map.iter().any(|row| row.chars().count() != dimension)
+    {
+        return Err(format!(
+            "Cage map must have 3 to 9 rows with one position per column each.\n{:?}",
+            cage_map
+        ));
+    }
+
+    //collect the positions per cage id in order of appearance
+    let mut cage_ids: Vec<char> = Vec::new();
+    let mut cages: Vec<Vec<usize>> = Vec::new();
+    for (row, row_ids) in cage_map.iter().enumerate() {
+        for (column, id) in row_ids.chars().enumerate() {
+            match cage_ids.iter().position(|&c| c == id) {
+                Some(index) => cages[index].push(row * 10 + column),
+                None => {
+                    cage_ids.push(id);
+                    cages.push(vec![row * 10 + column]);
+                }
+            }
+        }
+    }
+
+    let mut clues: Vec<Option<&str>> = vec![None; cage_ids.len()];
+    for clue_line in cage_clues {
+        let (index, clue) = match clue_line.split_whitespace().collect::<Vec<&str>>()[..] {
+            [id, clue] if id.chars().count() == 1 => {
+                match cage_ids.iter().position(|c| id.starts_with(*c)) {
+                    Some(index) => (index, clue),
+                    None => return Err(format!("Cage '{}' is not part of the cage map", id)),
+                }
+            }
+            _ => return Err(format!("No valid cage clue found: {}", clue_line)),
+        };
+        if clues[index].replace(clue).is_some() {
+            return Err(format!("Cage '{}' has more than one clue", cage_ids[index]));
+        }
+    }
+
+    let mut cage_lines: Vec<String> = Vec::new();
+    for ((id, cage), clue) in cage_ids.iter().zip(cages.iter()).zip(clues) {
+        if !is_connected(cage) {
+            return Err(format!("Cage '{}' is not connected: {:?}", id, cage));
+        }
+        let clue = clue.ok_or(format!("Cage '{}' has no clue", id))?;
+        let positions: Vec<String> = cage.iter().map(|p| format!("{:02}", p)).collect();
+        cage_lines.push(format!("{}{}", clue, positions.join(".")));
+    }
+
+    Ok(cage_lines)
+}
+
+/// Retrieves the rows of a solution in the JSON format, i.e. an object with the field "rows"
+pub fn get_solution_rows_from_json(json: &str) -> Result<Vec<String>, String> {
+    let json_solution: JsonSolution = serde_json::from_str(json)
+        .map_err(|e| format!("No valid JSON solution found: {}", e))?;
+    Ok(json_solution.rows)
+}
+
+/// Parses a line of a KenKen, e.g. "!6+00.01.02", into a cage of the JSON format
+fn get_json_cage(cage_line: &str) -> Result<JsonCage, String> {
+    let cage = cage_line.trim_start_matches('!');
+    let invalid_cage = || format!("No valid cage found: {}", cage_line);
+    let operation_index = cage.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid_cage)?;
+    let operation = cage[operation_index..].chars().next().ok_or_else(invalid_cage)?;
+
+    let mut cells: Vec<(usize, usize)> = Vec::new();
+    for cell in cage[operation_index + operation.len_utf8()..].split('.') {
+        match cell.parse::<usize>() {
+            Ok(position) if cell.len() == 2 => cells.push((position / 10, position % 10)),
+            _ => return Err(invalid_cage()),
+        }
+    }
+
+    Ok(JsonCage {
+        result: cage[..operation_index].parse().map_err(|_| invalid_cage())?,
+        operation,
+        cells,
+        distinct: cage_line.starts_with('!'),
+    })
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Checks if the given string is a Sudoku in the one line format,
+/// i.e. 81 positions with the digits 1 to 9 and '.' or '0' for open positions
+pub fn is_sudoku_line(positions: &str) -> bool {
+    positions.chars().count() == 81
+        && positions.chars().all(|c| c == '.' || c.is_ascii_digit())
+}
+
+/// Checks if the given string is a game ID of the puzzle "Keen", i.e. the parameters
+/// starting with the size, ':', the borders of the cages, ',' and the clues
+pub fn is_keen_id(game_id: &str) -> bool {
+    let Some((params, game_description)) = game_id.split_once(':') else {
+        return false;
+    };
+    let Some((borders, clues)) = game_description.split_once(',') else {
+        return false;
+    };
+    params.starts_with(|c: char| c.is_ascii_digit())
+        && params.chars().all(|c| c.is_ascii_alphanumeric())
+        && !borders.is_empty()
+        && borders.chars().all(|c| c == '_' || c.is_ascii_lowercase() || c.is_ascii_digit())
+        && !clues.is_empty()
+        && clues.chars().all(|c| "asmd".contains(c) || c.is_ascii_digit())
+}
+
+/// Retrieves the two cells (row * dimension + column) on both sides of an inner line
+/// of a Keen grid, first the vertical lines in reading order,
+/// afterwards the horizontal lines column by column
+fn get_keen_line_cells(line: usize, dimension: usize) -> (usize, usize) {
+    if line < dimension * (dimension - 1) {
+        let (row, column) = (line / (dimension - 1), line % (dimension - 1));
+        (row * dimension + column, row * dimension + column + 1)
+    } else {
+        let (column, row) = (line / (dimension - 1) - dimension, line % (dimension - 1));
+        (row * dimension + column, (row + 1) * dimension + column)
+    }
+}
+
+/// Retrieves the cages of the borders of a Keen game ID, each cage with its cells
+/// (row * dimension + column) in ascending order, the cages ordered by their first cell.
+///
+/// Each character counts the inner lines without border before the next border,
+/// '_' for none, 'a' to 'y' for 1 to 25 and 'z' for 26 lines without a following border.
+/// A character followed by a number is repeated this number of times.
+fn get_keen_cages(borders: &str, dimension: usize) -> Result<Vec<Vec<usize>>, String> {
+    let line_count = 2 * dimension * (dimension - 1);
+    let mut cage_of_cell: Vec<usize> = (0..dimension * dimension).collect();
+    let mut line = 0;
+    let mut border_chars = borders.chars().peekable();
+    while let Some(c) = border_chars.next() {
+        let mut count = String::new();
+        while let Some(digit) = border_chars.next_if(|c| c.is_ascii_digit()) {
+            count.push(digit);
+        }
+        let repetitions: usize = if count.is_empty() {
+            1
+        } else {
+            count
+                .parse()
+                .map_err(|_| format!("No valid repetition count '{}' in Keen borders", count))?
+        };
+        let run = match c {
+            '_' => 0,
+            'a'..='z' => (c as u8 - b'a') as usize + 1,
+            _ => return Err(format!("No valid character '{}' in Keen borders", c)),
+        };
+        for _ in 0..repetitions {
+            //each repetition passes at least one line, so a count beyond the grid stops here
+            if line > line_count {
+                return Err("Keen borders exceed the grid".to_string());
+            }
+            for _ in 0..run {
+                if line >= line_count {
+                    return Err("Keen borders exceed the grid".to_string());
+                }
+                //merge the cages on both sides of the line without border
+                let (cell, neighbour) = get_keen_line_cells(line, dimension);
+                let (cage, other_cage) = (cage_of_cell[cell], cage_of_cell[neighbour]);
+                let merged_cage = cage.min(other_cage);
+                cage_of_cell
+                    .iter_mut()
+                    .filter(|cage_index| **cage_index == cage || **cage_index == other_cage)
+                    .for_each(|cage_index| *cage_index = merged_cage);
+                line += 1;
+            }
+            //'z' has no following border
+            if run != 26 {
+                line += 1;
+            }
+        }
+    }
+    //the last border is behind the last line
+    if line != line_count + 1 {
+        return Err(format!("Keen borders don't match the size {}", dimension));
+    }
+
+    let mut cages: Vec<Vec<usize>> = Vec::new();
+    for (cell, &cage) in cage_of_cell.iter().enumerate() {
+        match cages.iter_mut().find(|cells| cells[0] == cage) {
+            Some(cells) => cells.push(cell),
+            None => cages.push(vec![cell]),
+        }
+    }
+    Ok(cages)
+}
+
 #[cfg(test)]
 mod kk_loader_tests {
     use super::*;
@@ -148,6 +1467,29 @@ mod kk_loader_tests {
         );
         //assert_eq!(valid_sudoku_file.puzzle_string, "-5-.--8.269|--2.-43.---|--9.---.---|--7.---.---|---.-9-.-4-|5-3.---.-9-|---.-24.6-5|6--.---.--3|-4-.-8-.---");
 
+        let distinct_kenken = PuzzleAsString::new_from_raw_string(
+            "KenKen without repeated digits\nKenKen DistinctCages\n6+00.01.02".to_string(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(distinct_kenken.game_type, KenKen);
+        assert!(distinct_kenken.has_rule(Rule::DistinctCages));
+        assert!(!valid_kenken_file.has_rule(Rule::DistinctCages));
+
+        let chess_sudoku = PuzzleAsString::new_from_raw_string(
+            "Chess Sudoku\nSudoku AntiKnight AntiKing\n".to_string(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(chess_sudoku.rules, vec!(Rule::AntiKnight, Rule::AntiKing));
+
+        let invalid_rule = PuzzleAsString::new_from_raw_string(
+            "KenKen with unknown rule\nKenKen NoRule\n6+00.01.02".to_string(),
+            true,
+        );
+        assert!(invalid_rule.unwrap_err().ends_with("line 2, column 8: Unknown rule 'NoRule' \
+            - use one of DistinctCages, AntiKnight, AntiKing, Hyper"));
+
         let invalid_file = PuzzleAsString::new_from_file("test_fail");
         assert_eq!(invalid_file.is_err(), true);
         assert_eq!(
@@ -156,6 +1498,266 @@ mod kk_loader_tests {
         );
     }
 
+    #[test]
+    fn check_load_sudoku_lines() {
+        let sudoku_line = PuzzleAsString::new_from_sudoku_line(
+            ".5...8269..2.43.....9........7..........9..4.5.3....9.....246.56.......3.4..8....",
+            true,
+        )
+        .unwrap();
+        let sudoku_file = PuzzleAsString::new_from_file("S-1.txt").unwrap();
+        assert_eq!(sudoku_line.game_type, Sudoku);
+        assert_eq!(
+            sudoku_line.puzzle_string,
+            sudoku_file
+                .puzzle_string
+                .iter()
+                .map(|row| row.replace('.', ""))
+                .collect::<Vec<String>>()
+        );
+        assert_eq!(sudoku_line.description.len(), 81);
+
+        let sudokus = PuzzleAsString::new_list_from_file("S-Lines.txt").unwrap();
+        assert_eq!(sudokus.len(), 2);
+        assert_eq!(sudokus[0].puzzle_string, sudoku_line.puzzle_string);
+        assert_eq!(sudokus[0].description, "Sudoku Expert - https://sudoku.com/de/experte/");
+        assert_eq!(sudokus[1].puzzle_string[0], "29---3--4");
+
+        //a file with a single puzzle is a list of one puzzle
+        assert_eq!(PuzzleAsString::new_list_from_file("S-1.txt").unwrap().len(), 1);
+
+        for invalid_line in ["12345", &"x".repeat(81), &"1".repeat(82)] {
+            assert!(PuzzleAsString::new_from_sudoku_line(invalid_line, true).is_err());
+        }
+    }
+
+    #[test]
+    fn check_metadata_headers() {
+        let kenken = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
+        let headers = "Source: newdoku.com\nexpected solution: 2341,1234\nrules: DistinctCages\n";
+        let raw_string = kenken
+            .format_raw_string(&kenken.puzzle_string, &[])
+            .replacen("KenKen\n", &format!("{}KenKen\n", headers), 1);
+        let kenken_headers = PuzzleAsString::new_from_raw_string(raw_string, true).unwrap();
+        assert_eq!(kenken_headers.description, kenken.description);
+        assert_eq!(kenken_headers.puzzle_string, kenken.puzzle_string);
+        assert_eq!(kenken_headers.rules, vec![Rule::DistinctCages]);
+        assert_eq!(kenken_headers.metadata.len(), 2);
+        assert_eq!(kenken_headers.get_metadata("source").unwrap(), "newdoku.com");
+        assert_eq!(kenken_headers.get_expected_solution().unwrap(), "2341,1234");
+
+        //the headers are kept as written in their order when saving the puzzle
+        let saved = kenken_headers.format_raw_string(&kenken_headers.puzzle_string, &[]);
+        assert!(saved.contains("\nSource: newdoku.com\nexpected solution: 2341,1234\n"));
+        let reloaded = PuzzleAsString::new_from_raw_string(saved, true).unwrap();
+        assert_eq!(reloaded.metadata, kenken_headers.metadata);
+        assert_eq!(reloaded.rules, kenken_headers.rules);
+
+        for invalid_headers in [
+            "id: 1\nid: 2\nKenKen\n3c00\n",
+            "id: 1\nID: 2\nKenKen\n3c00\n",
+            "rules: Diagonal\nKenKen\n3c00\n",
+            "id: 1\n",
+        ] {
+            let raw_string = format!("invalid headers\n{}", invalid_headers);
+            assert!(PuzzleAsString::new_from_raw_string(raw_string, true).is_err());
+        }
+    }
+
+    #[test]
+    fn check_cage_problems() {
+        let kenken = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
+        assert!(kenken.get_cage_problems().is_empty());
+        assert!(PuzzleAsString::new_from_file("S-1.txt").unwrap().get_cage_problems().is_empty());
+
+        let invalid_kenken = PuzzleAsString::new_from_raw_string(
+            "KenKen with invalid cages\nKenKen\n3-00.01.02\n6+10.12\n2:11.21\n3c20.22\n1c21\n"
+                .to_string(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            invalid_kenken.get_cage_problems(),
+            vec![
+                "Cage 1 '3-00.01.02': operation '-' needs 2 position(s), found 3",
+                "Cage 2 '6+10.12': positions are not connected",
+                "Cage 4 '3c20.22': operation 'c' needs 1 position(s), found 2",
+                "Cage 4 '3c20.22': positions are not connected",
+                "Cage 5 '1c21': position 21 is already part of cage 3",
+            ]
+        );
+
+        //a declared dimension finds positions outside of the grid and gaps
+        let declared_kenken = PuzzleAsString::new_from_raw_string(
+            "KenKen 4 x 4\ndimension: 3\nKenKen\n6+00.01.02\n6+10.11.12\n6+20.21.22.23\n"
+                .to_string(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            declared_kenken.get_cage_problems(),
+            vec!["Cage 3 '6+20.21.22.23': positions 23 are outside of the 3 x 3 grid"]
+        );
+        let gap_kenken = PuzzleAsString::new_from_raw_string(
+            "KenKen with gaps\nKenKen\n6+00.01.02\n3+10.11\n6+20.21.22\n".to_string(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            gap_kenken.get_cage_problems(),
+            vec!["Positions 12 aren't part of any cage"]
+        );
+    }
+
+    #[test]
+    fn check_keen_ids() {
+        let kenken = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
+        let keen_id = "4:ab_4a3_2ba2_,s1a8m6s2m16a6";
+        assert_eq!(kenken.to_keen_id().unwrap(), keen_id);
+        let kenken_from_id = PuzzleAsString::new_from_keen_id(keen_id, true).unwrap();
+        assert_eq!(kenken_from_id.puzzle_string, kenken.puzzle_string);
+        assert_eq!(kenken_from_id.get_metadata("keen params").unwrap(), "4");
+        assert_eq!(kenken_from_id.to_keen_id().unwrap(), keen_id);
+
+        //a cage per row has 72 inner lines without border, i.e. 'z' twice followed by 't'
+        let row_cages: Vec<String> = (0..9)
+            .map(|row| {
+                let positions: Vec<String> = (0..9).map(|c| format!("{}{}", row, c)).collect();
+                format!("45+{}", positions.join("."))
+            })
+            .collect();
+        let rows_kenken = PuzzleAsString::new_from_raw_string(
+            format!("Rows\nKenKen\n{}", row_cages.join("\n")),
+            true,
+        )
+        .unwrap();
+        let rows_id = rows_kenken.to_keen_id().unwrap();
+        assert_eq!(rows_id, format!("9:z2t_72,{}", "a45".repeat(9)));
+        let rows_from_id = PuzzleAsString::new_from_keen_id(&rows_id, true).unwrap();
+        assert_eq!(rows_from_id.puzzle_string, row_cages);
+
+        let kenkens = PuzzleAsString::new_list_from_file("KK-Keen.txt").unwrap();
+        assert_eq!(kenkens.len(), 2);
+        assert_eq!(kenkens[0].puzzle_string, kenken.puzzle_string);
+        assert_eq!(
+            kenkens[1].puzzle_string,
+            PuzzleAsString::new_from_file("KK-Dim9-1.txt").unwrap().puzzle_string
+        );
+
+        for invalid_id in [
+            "4:ab_4a3_2ba2,s1a8m6s2m16a6",
+            "4:ab_4a3_2ba2_,s1a8m6s2m16",
+            "4:ab_4a3_2ba2_,s1a8m6s2m16a6a1",
+            "2:a_a,a3",
+            "4:ab_4a3_2ba2_",
+        ] {
+            assert!(PuzzleAsString::new_from_keen_id(invalid_id, true).is_err(), "{}", invalid_id);
+        }
+        for (invalid_id, error) in [
+            ("3:_99999999999,a1", "Keen borders exceed the grid"),
+            ("3:z99999999999,a1", "Keen borders exceed the grid"),
+            (
+                "3:_99999999999999999999,a1",
+                "No valid repetition count '99999999999999999999' in Keen borders",
+            ),
+        ] {
+            assert_eq!(PuzzleAsString::new_from_keen_id(invalid_id, true).unwrap_err(), error);
+        }
+        let distinct_kenken = PuzzleAsString::new_from_raw_string(
+            "Distinct\nKenKen DistinctCages\n6+00.01.02\n6+10.11.12\n6+20.21.22\n".to_string(),
+            true,
+        )
+        .unwrap();
+        assert!(distinct_kenken.to_keen_id().is_err());
+    }
+
+    #[test]
+    fn check_kenken_cage_map() {
+        let kenken_map = PuzzleAsString::new_from_file("KK-Map-1.txt").unwrap();
+        let kenken_list = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
+        assert_eq!(kenken_map.puzzle_string, kenken_list.puzzle_string);
+        assert!(kenken_map.sections.is_empty());
+
+        //converting to the cage map and back keeps the cages
+        let cage_map = kenken_list.to_cage_map().unwrap();
+        assert!(cage_map.contains("AABB\nCCBD\nCEFD\nEEFF\n[Cages]\nA 1-\n"));
+        let converted = PuzzleAsString::new_from_raw_string(cage_map, true).unwrap();
+        assert_eq!(converted.puzzle_string, kenken_list.puzzle_string);
+        assert!(PuzzleAsString::new_from_file("S-1.txt").unwrap().to_cage_map().is_err());
+
+        let header = "invalid cage map\nKenKen\n";
+        for invalid_map in [
+            "AAB\nCAB\nCCB\n[Cages]\nA 3+\nB 6+\n",
+            "AAB\nCAB\nCCB\n[Cages]\nA 3+\nB 6+\nC 5+\nD 1c\n",
+            "AAB\nCAB\nCCB\n[Cages]\nA 3+\nA 4+\nB 6+\nC 5+\n",
+            "ABA\nCBB\nCCB\n[Cages]\nA 3+\nB 6+\nC 5+\n",
+            "AAB\nCAB\nCC\n[Cages]\nA 3+\nB 6+\nC 5+\n",
+            "AAB\nCAB\nCCB\n[Cages]\nA3+\nB 6+\nC 5+\n",
+        ] {
+            assert!(
+                PuzzleAsString::new_from_raw_string(format!("{}{}", header, invalid_map), true)
+                    .is_err(),
+                "{}",
+                invalid_map
+            );
+        }
+    }
+
+    #[test]
+    fn check_json_import_and_export() {
+        //a KenKen survives the round trip to JSON
+        let kenken = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
+        let kenken_json = kenken.to_json().unwrap();
+        assert!(kenken_json.contains(r#"{"result":1,"operation":"-","cells":[[0,0],[0,1]]}"#));
+        let kenken_from_json = PuzzleAsString::new_from_json(&kenken_json, true).unwrap();
+        assert_eq!(kenken_from_json.puzzle_string, kenken.puzzle_string);
+        assert_eq!(kenken_from_json.description, kenken.description);
+
+        //givens, sections and metadata of a JSON puzzle
+        let json = r#"{
+            "game_type": "KenKen",
+            "rules": ["AntiKing"],
+            "dimension": 3,
+            "cages": [
+                {"result": 6, "operation": "+", "cells": [[0, 0], [0, 1], [0, 2]], "distinct": true},
+                {"result": 6, "operation": "+", "cells": [[1, 0], [1, 1], [1, 2]]},
+                {"result": 6, "operation": "+", "cells": [[2, 0], [2, 1], [2, 2]]}
+            ],
+            "givens": ["-2-", "---", "---"],
+            "sections": {"Inequalities": ["00<01"]},
+            "metadata": {"author": "someone"}
+        }"#;
+        let kenken_from_json = PuzzleAsString::new_from_json(json, true).unwrap();
+        assert_eq!(kenken_from_json.description, "KenKen puzzle");
+        assert_eq!(kenken_from_json.rules, vec!(Rule::AntiKing));
+        assert_eq!(kenken_from_json.puzzle_string[0], "!6+00.01.02");
+        assert_eq!(kenken_from_json.get_section("Givens").unwrap()[0], "-2-");
+        assert_eq!(kenken_from_json.get_section("Inequalities").unwrap()[0], "00<01");
+        assert_eq!(kenken_from_json.get_metadata("author").unwrap(), "someone");
+        let kenken_json = kenken_from_json.to_json().unwrap();
+        assert!(kenken_json.contains(r#""givens":["-2-","---","---"]"#));
+        assert!(kenken_json.contains(r#""metadata":{"author":"someone"}"#));
+
+        //other puzzles keep their rows
+        let sudoku = PuzzleAsString::new_from_file("S-Jigsaw-1.txt").unwrap();
+        let sudoku_from_json =
+            PuzzleAsString::new_from_json(&sudoku.to_json().unwrap(), true).unwrap();
+        assert_eq!(sudoku_from_json.puzzle_string, sudoku.puzzle_string);
+        assert_eq!(sudoku_from_json.get_regions(), sudoku.get_regions());
+
+        let wrong_dimension = json.replace("\"dimension\": 3", "\"dimension\": 4");
+        assert!(PuzzleAsString::new_from_json(&wrong_dimension, true)
+            .unwrap_err()
+            .starts_with("Dimension 4 doesn't match"));
+        assert!(PuzzleAsString::new_from_json(r#"{"game_type": "Chess"}"#, true).is_err());
+        assert!(get_json_cage("6+00.1").is_err());
+
+        assert_eq!(
+            get_solution_rows_from_json(r#"{"game_type": "Sudoku", "rows": ["123", "231"]}"#),
+            Ok(vec!("123".to_string(), "231".to_string()))
+        );
+    }
+
     #[test]
     fn check_get_dimension() {
         let kenken_1 = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
@@ -166,5 +1768,123 @@ mod kk_loader_tests {
 
         let sudoku_1 = PuzzleAsString::new_from_file("S-1.txt").unwrap();
         assert_eq!(sudoku_1.get_dimension(), Ok(9));
+
+        let futoshiki_1 = PuzzleAsString::new_from_file("F-1.txt").unwrap();
+        assert_eq!(futoshiki_1.get_dimension(), Ok(5));
+
+        let skyscrapers_1 = PuzzleAsString::new_from_file("SK-1.txt").unwrap();
+        assert_eq!(skyscrapers_1.get_dimension(), Ok(5));
+
+        let kakuro_1 = PuzzleAsString::new_from_file("KA-1.txt").unwrap();
+        assert_eq!(kakuro_1.get_dimension(), Ok(5));
+        assert_eq!(kakuro_1.get_digits(), Ok((1..=9).collect()));
+
+        let str8ts_1 = PuzzleAsString::new_from_file("ST-1.txt").unwrap();
+        assert_eq!(str8ts_1.get_dimension(), Ok(6));
+        assert_eq!(
+            str8ts_1.get_black_positions(),
+            Ok(vec!(0, 3, 12, 23, 25, 30, 32, 43, 52, 55))
+        );
+
+        //3 lines of clues are rejected when loading, the dimension can't be determined
+        let raw_skyscrapers_2 = "Sky\nSkyscrapers\n--1-2\n--24-\n33---".to_string();
+        assert!(PuzzleAsString::new_from_raw_string(raw_skyscrapers_2, true).is_err());
+        let mut skyscrapers_2 = skyscrapers_1.clone();
+        skyscrapers_2.puzzle_string.pop();
+        assert!(skyscrapers_2.get_dimension().is_err());
+    }
+
+    #[test]
+    fn check_get_digits_and_symbols() {
+        let kenken = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
+        assert_eq!(kenken.get_digits(), Ok(vec!(1, 2, 3, 4)));
+        assert_eq!(kenken.get_symbols(&[1, 2, 3, 4]), Ok(vec!('1', '2', '3', '4')));
+
+        let zero_based = PuzzleAsString::new_from_raw_string(
+            "Zero based\nKenKen\n0-00.01\n5+02.03.12\n0*10.11.20\n2-13.23\n0*21.30.31\n\
+             3+22.32.33\n[Digits]\n3012\n[Symbols]\nABCD"
+                .to_string(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(zero_based.get_digits(), Ok(vec!(0, 1, 2, 3)));
+        assert_eq!(zero_based.get_symbols(&[0, 1, 2, 3]), Ok(vec!('A', 'B', 'C', 'D')));
+
+        //the wrong sections are rejected when loading and by the getters
+        let wrong_count = PuzzleAsString::new_from_raw_string(
+            "Wrong count\nKenKen\n0-00.01\n5+02.03.12\n0*10.11.20\n2-13.23\n0*21.30.31\n\
+             3+22.32.33\n[Digits]\n01234\n[Symbols]\nAB-D"
+                .to_string(),
+            true,
+        );
+        assert!(wrong_count.is_err());
+        let mut wrong_count = zero_based.clone();
+        wrong_count.sections = vec![
+            ("Digits".to_string(), vec!["01234".to_string()]),
+            ("Symbols".to_string(), vec!["AB-D".to_string()]),
+        ];
+        assert!(wrong_count.get_digits().is_err());
+        assert!(wrong_count.get_symbols(&[0, 1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn check_get_regions() {
+        //without region map the regions are the 3x3 quadrants
+        let sudoku = PuzzleAsString::new_from_file("S-1.txt").unwrap();
+        let regions = sudoku.get_regions().unwrap();
+        assert_eq!(regions.len(), 9);
+        assert_eq!(regions[0], vec!(0, 1, 2, 10, 11, 12, 20, 21, 22));
+        assert_eq!(regions[8], vec!(66, 67, 68, 76, 77, 78, 86, 87, 88));
+
+        let jigsaw = PuzzleAsString::new_from_file("S-Jigsaw-1.txt").unwrap();
+        assert_eq!(jigsaw.puzzle_string.len(), 9);
+        assert_eq!(jigsaw.get_section("Regions").unwrap().len(), 9);
+        let regions = jigsaw.get_regions().unwrap();
+        assert_eq!(regions.len(), 9);
+        assert_eq!(regions[0], vec!(0, 1, 2, 3, 4, 10, 20, 21, 22));
+
+        //region '1' with 10 positions and region '2' with 8 positions,
+        //the wrong region maps are rejected when loading and by get_regions
+        let wrong_regions = |region_map: &str| -> PuzzleAsString {
+            let raw_jigsaw = format!("Jigsaw\nSudoku\n[Regions]\n{}", region_map);
+            assert!(PuzzleAsString::new_from_raw_string(raw_jigsaw, true).is_err());
+            let mut wrong_jigsaw = jigsaw.clone();
+            wrong_jigsaw.sections = vec![(
+                "Regions".to_string(),
+                region_map.split('\n').map(|row| row.to_string()).collect(),
+            )];
+            wrong_jigsaw
+        };
+        let wrong_size = wrong_regions(
+            "111113333\n112222233\n111225333\n444425666\n\
+             445555566\n445756666\n477779999\n778888999\n778888899",
+        );
+        assert_eq!(
+            wrong_size.get_regions(),
+            Err("Region '1' must have 9 positions, found 10".to_string())
+        );
+
+        //one position of region '3' is swapped with one of region '9'
+        let not_connected = wrong_regions(
+            "111113333\n122222233\n111225933\n444425666\n\
+             445555566\n445756666\n477779999\n778888999\n778888893",
+        );
+        assert!(not_connected
+            .get_regions()
+            .unwrap_err()
+            .starts_with("Region '3' is not connected"));
+    }
+    #[test]
+    fn check_read_input() {
+        let raw_kenken = read_input(" KK-Dim4-1.txt ").unwrap();
+        assert!(raw_kenken.starts_with("Newdoku.com KenKen-puzzle nr.: 1278350"));
+        assert!(read_input("KK-Missing.txt")
+            .unwrap_err()
+            .starts_with("Error reading file."));
+
+        //the content read from a file or stdin is loaded like the file itself
+        let kenkens = PuzzleAsString::new_list_from_file("KK-Dim4-1.txt").unwrap();
+        assert_eq!(kenkens.len(), 1);
+        assert_eq!(PuzzleAsString::new_from_file_content(raw_kenken).unwrap(), kenkens[0]);
     }
 }
@@ -1,30 +1,73 @@
 //! kk_puzzle is part of kenken_solve and provides the representation of the puzzle to be solved
 //!
 //! A puzzle consists of
-//!  * the type of the puzzle, i.e. KenKen or Sudoku
+//!  * the type of the puzzle, i.e. KenKen, Sudoku, Futoshiki, Skyscrapers, Kakuro, Str8ts,
+//!    Inshi no heya or a multi grid Sudoku
 //!  * the dimension (3 to 9) of the puzzle (for sudoku this is always 9)
-//!  * a field, representing a representation of all set group-solutions
+//!  * the digits of the puzzle (usually 1 to dimension) and the symbols representing them
+//!  * a field, representing a representation of all set group-solutions,
+//!    positions without a digit are marked as EMPTY,
+//!    for multi grid Sudokus each grid has its own 100 positions
+//!  * the offsets of the grids of a multi grid Sudoku within the rows of the puzzle
+//!  * the black positions, which are not part of any group (Kakuro and Str8ts)
 //!  * a list of undecided groups (with more than one option left)
-//!  * a blocklist, holding blocklisted digits for each field position
+//!  * a blocklist, holding blocklisted digits for each field position,
+//!    initially the digits of the wrong parity for the shaded positions of a parity map
+//!  * a list of additional constraints, e.g. inequalities between neighbouring positions
+//!    or the clues of a Skyscrapers puzzle, the shaded positions of a parity map are kept
+//!    as constraints only to report contradicting digits
+//!  * the setup of the puzzle, i.e. the description, the headers, the rules, the given digits
+//!    and the regions, which is written together with the groups and the constraints
+//!    to save the puzzle as text
 //!
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::sync::Arc;
 use colored::*;
 
 use crate::kk_block_list::BlockList;
+use crate::kk_constraint::{Constraint, ConstraintList, ConstraintParser, KING_MOVES, KNIGHT_MOVES};
 use crate::kk_group::Group;
 use crate::kk_load::GameType;
-use crate::kk_load::GameType::Sudoku;
-use crate::kk_load::PuzzleAsString;
+use crate::kk_load::GameType::{
+    Futoshiki, InshiNoHeya, Kakuro, KenKen, MultiSudoku, Skyscrapers, Str8ts, Sudoku,
+};
+use crate::kk_load::{PuzzleAsString, Rule};
 
-#[derive(Debug, Clone,Getters)]
+/// Marker for a position of the field without a digit,
+/// which is different from all digits (including 0 in zero based puzzles)
+pub const EMPTY: usize = usize::MAX;
+
+#[derive(Debug, Clone, Getters)]
 pub struct Puzzle {
     game_type: GameType,
     dimension: usize,
+    digits: Vec<usize>,
+    symbols: Vec<char>,
     normal_group_direction:bool,
     solution: Vec<usize>,
+    grid_offsets: Vec<(usize, usize)>,
+    black_positions: Vec<usize>,
     block_list: BlockList,
+    constraints: ConstraintList,
     groups: Vec<Group>,
+    setup: Arc<PuzzleSetup>,
+}
+
+/// The setup of a puzzle, which doesn't change while solving the puzzle,
+/// but is needed to save the puzzle as text (see Puzzle::to_puzzle_string)
+/// * the description and the headers of the puzzle file
+/// * the rules of the puzzle, e.g. AntiKnight
+/// * the given digits of the field (EMPTY for all other positions)
+/// * the regions of a Sudoku and the extra regions of the section "Extra Regions"
+#[derive(Debug, Clone, Getters)]
+pub struct PuzzleSetup {
+    description: String,
+    metadata: Vec<(String, String)>,
+    rules: Vec<Rule>,
+    givens: Vec<usize>,
+    regions: Vec<Vec<usize>>,
+    extra_regions: Vec<Vec<usize>>,
 }
 
 impl Puzzle {
@@ -33,27 +76,167 @@ impl Puzzle {
         Puzzle {
             game_type: old_field.game_type,
             dimension: old_field.dimension,
+            digits: old_field.digits.clone(),
+            symbols: old_field.symbols.clone(),
             normal_group_direction:old_field.normal_group_direction,
             solution: old_field.solution.clone(),
+            grid_offsets: old_field.grid_offsets.clone(),
+            black_positions: old_field.black_positions.clone(),
             block_list: old_field.block_list.clone(),
+            constraints: old_field.constraints.clone(),
             groups: Vec::new(),
+            setup: Arc::clone(&old_field.setup),
         }
     }
 
     pub fn new_from_puzzle_file(puzzle_file: PuzzleAsString) -> Result<Self, String> {
+        //all problems of the cages of a KenKen are reported at once
+        let cage_problems = puzzle_file.get_cage_problems();
+        if !cage_problems.is_empty() {
+            return Err(format!(
+                "Found {} problem(s) in the cages:\n{}",
+                cage_problems.len(),
+                cage_problems.join("\n")
+            ));
+        }
+
+        let digits = puzzle_file.get_digits()?;
         let mut new_puzzle = Puzzle {
             game_type: *puzzle_file.game_type(),
             dimension: puzzle_file.get_dimension()?,
+            symbols: puzzle_file.get_symbols(&digits)?,
+            digits,
             normal_group_direction: *puzzle_file.normal_group_direction(),
-            solution: vec![0; 90],
+            solution: vec![EMPTY; 90],
+            grid_offsets: Vec::new(),
+            black_positions: Vec::new(),
             block_list: BlockList::new(),
+            constraints: ConstraintList::new(),
             groups: Vec::new(),
+            setup: Arc::new(PuzzleSetup {
+                description: puzzle_file.description().clone(),
+                metadata: puzzle_file.metadata().clone(),
+                rules: puzzle_file.rules().clone(),
+                givens: Vec::new(),
+                regions: Vec::new(),
+                extra_regions: Vec::new(),
+            }),
         };
 
-        if new_puzzle.game_type == Sudoku {
-            new_puzzle.initialize_sudoku_from_definition(puzzle_file.puzzle_string())?;
-        } else {
-            new_puzzle.initialize_kenken_from_definition(puzzle_file.puzzle_string())?;
+        //the constraints must be known before the groups are initialized,
+        //each section holds one constraint per line
+        //the crusts of a sandwich are the lowest and the highest digit of the puzzle
+        let dimension = new_puzzle.dimension;
+        let digits = new_puzzle.digits.clone();
+        let constraint_sections: [(&str, ConstraintParser); 5] = [
+            ("Inequalities", &|line| Constraint::new_inequality(dimension, line)),
+            ("Adjacent", &|line| Constraint::new_adjacent(dimension, line)),
+            ("Sandwiches", &|line| Constraint::new_sandwich(&digits, line)),
+            ("Thermometers", &|line| Constraint::new_thermometer(dimension, line)),
+            ("Arrows", &|line| Constraint::new_arrow(dimension, line)),
+        ];
+        for (section_name, new_constraint) in constraint_sections {
+            if let Some(constraint_lines) = puzzle_file.get_section(section_name) {
+                for constraint_line in constraint_lines {
+                    new_puzzle.constraints.add(new_constraint(constraint_line)?);
+                }
+            }
+        }
+
+        //the chess rules apply to each grid of the puzzle
+        let grid_count = match new_puzzle.game_type {
+            MultiSudoku => puzzle_file.get_grid_offsets()?.len(),
+            _ => 1,
+        };
+        for (rule, moves) in [(Rule::AntiKnight, KNIGHT_MOVES), (Rule::AntiKing, KING_MOVES)] {
+            if puzzle_file.has_rule(rule) {
+                for grid in 0..grid_count {
+                    Constraint::new_chess_moves(new_puzzle.dimension, grid * 100, &moves)
+                        .into_iter()
+                        .for_each(|constraint| new_puzzle.constraints.add(constraint));
+                }
+            }
+        }
+
+        //shaded positions hold only even (or odd) digits, the other digits are blocklisted,
+        //the parity constraint reports given digits and solutions of the wrong parity
+        let parity_positions = puzzle_file.get_parity_positions()?;
+        if new_puzzle.game_type == MultiSudoku && !parity_positions.is_empty() {
+            return Err("A parity map isn't supported for multi grid Sudokus".to_string());
+        }
+        for (position, remainder) in parity_positions {
+            let blocked_digits: HashSet<usize> = new_puzzle
+                .digits
+                .iter()
+                .filter(|&&digit| digit % 2 != remainder)
+                .copied()
+                .collect();
+            new_puzzle.block_list.add_position_block_list(position, &blocked_digits);
+            new_puzzle.constraints.add(Constraint::new_parity(position, remainder));
+        }
+
+        //additional regions of distinct digits, e.g. of a Hyper Sudoku
+        let extra_regions = puzzle_file.get_extra_regions()?;
+        if ![Sudoku, KenKen].contains(&new_puzzle.game_type) && !extra_regions.is_empty() {
+            return Err(format!(
+                "Extra regions aren't supported for {:?}",
+                new_puzzle.game_type
+            ));
+        }
+        //the regions are kept to save the puzzle, the regions of the rule Hyper are given by the rule
+        let setup = Arc::make_mut(&mut new_puzzle.setup);
+        if [Sudoku, MultiSudoku].contains(&new_puzzle.game_type) {
+            setup.regions = puzzle_file.get_regions()?;
+        }
+        if let Some(extra_region_lines) = puzzle_file.get_section("Extra Regions") {
+            setup.extra_regions = extra_regions[extra_regions.len() - extra_region_lines.len()..].to_vec();
+        }
+
+        match new_puzzle.game_type {
+            Sudoku => new_puzzle.initialize_sudoku_from_definition(
+                puzzle_file.puzzle_string(),
+                &[puzzle_file.get_regions()?, extra_regions].concat(),
+            )?,
+            Futoshiki => {
+                new_puzzle.initialize_futoshiki_from_definition(puzzle_file.puzzle_string())?
+            }
+            Skyscrapers => {
+                new_puzzle.initialize_skyscrapers_from_definition(puzzle_file.puzzle_string())?
+            }
+            MultiSudoku => new_puzzle.initialize_multi_sudoku_from_definition(
+                puzzle_file.puzzle_string(),
+                puzzle_file.get_grid_offsets()?,
+                &puzzle_file.get_regions()?,
+            )?,
+            Str8ts => new_puzzle.initialize_str8ts_from_definition(
+                puzzle_file.puzzle_string(),
+                puzzle_file.get_black_positions()?,
+            )?,
+            Kakuro => new_puzzle.initialize_kakuro_from_definition(
+                puzzle_file.puzzle_string(),
+                puzzle_file.get_section("Clues"),
+            )?,
+            KenKen => new_puzzle.initialize_kenken_from_definition(
+                puzzle_file.puzzle_string(),
+                puzzle_file.get_section("Givens"),
+                &extra_regions,
+                puzzle_file.has_rule(Rule::DistinctCages),
+            )?,
+            InshiNoHeya => {
+                new_puzzle.initialize_inshi_no_heya_from_definition(puzzle_file.puzzle_string())?
+            }
+        };
+
+        //the digits known before solving are the given digits
+        Arc::make_mut(&mut new_puzzle.setup).givens = new_puzzle.solution.clone();
+
+        //given digits must not contradict the constraints, e.g. a parity map
+        if let Some(constraint) = new_puzzle.constraints.get_violations(&new_puzzle.solution).first() {
+            return Err(format!(
+                "Given digits contradict the constraint '{}' at positions {:?}",
+                constraint.relation(),
+                constraint.positions()
+            ));
         }
 
         Ok(new_puzzle)
@@ -62,29 +245,75 @@ impl Puzzle {
     fn initialize_sudoku_from_definition(
         &mut self,
         definition: &Vec<String>,
+        regions: &[Vec<usize>],
     ) -> Result<&str, String> {
         //derive field from input strings
-        //remember for addressing each row contains 10 digits, hence the join with a 0
-        //the length of the field must be 89 = 8*10+9
-        self.solution = definition
-            .join("0")
-            .replace(".", "")
-            .replace("-", "0")
-            .chars()
-            .map(|c| c.to_digit(10).unwrap() as usize)
-            .collect();
-        if self.solution.len() != 89 {
-            return Err(format!("No valid Sudoku found.\n{:?}", self.solution));
+        //remember for addressing each row contains 10 digits
+        if definition.len() != 9 {
+            return Err(format!("No valid Sudoku found.\n{:?}", definition));
         };
+        for (row, row_string) in definition.iter().enumerate() {
+            self.set_row_from_definition(row, row_string)?;
+        }
+
+        self.add_sudoku_regions(regions, 0)
+    }
+
+    fn initialize_multi_sudoku_from_definition(
+        &mut self,
+        definition: &[String],
+        grid_offsets: Vec<(usize, usize)>,
+        regions: &[Vec<usize>],
+    ) -> Result<&str, String> {
+        let rows: Vec<Vec<char>> = definition.iter().map(|row| row.chars().collect()).collect();
+        self.solution = vec![EMPTY; 100 * grid_offsets.len()];
+
+        //first position of each cell of the rows, a cell shared by several grids
+        //gets an equality constraint between the positions of the grids
+        let mut cell_positions: HashMap<(usize, usize), usize> = HashMap::new();
+        for (grid, &(row_offset, column_offset)) in grid_offsets.iter().enumerate() {
+            for (row, column) in (0..9).flat_map(|row| (0..9).map(move |column| (row, column))) {
+                let cell = (row_offset + row, column_offset + column);
+                let position = grid * 100 + row * 10 + column;
+                self.solution[position] = match rows.get(cell.0).and_then(|r| r.get(cell.1)) {
+                    Some('-') => EMPTY,
+                    Some(&c) => match self.get_digit(c) {
+                        Some(digit) => digit,
+                        None => return Err(format!("No valid digit '{}' in grid {}", c, grid)),
+                    },
+                    None => return Err(format!("Grid {} exceeds the rows of the puzzle", grid)),
+                };
 
-        for quadrant in 0..9 {
+                match cell_positions.get(&cell) {
+                    Some(&shared_position) => self
+                        .constraints
+                        .add(Constraint::new_equality(shared_position, position)),
+                    None => _ = cell_positions.insert(cell, position),
+                }
+            }
+        }
+        self.grid_offsets = grid_offsets;
+
+        for grid in 0..self.grid_offsets.len() {
+            let grid_regions: Vec<Vec<usize>> = regions
+                .iter()
+                .map(|region| region.iter().map(|position| grid * 100 + position).collect())
+                .collect();
+            self.add_sudoku_regions(&grid_regions, grid)?;
+        }
+
+        Ok("ok")
+    }
+
+    /// Adds a group of disjunctive digits for the open positions of each region
+    /// of the Sudoku grid with the given index
+    fn add_sudoku_regions(&mut self, regions: &[Vec<usize>], grid: usize) -> Result<&str, String> {
+        for (region_index, region) in regions.iter().enumerate() {
             let mut constants: HashSet<usize> = HashSet::new();
             let mut positions: Vec<usize> = Vec::new();
-            //fetch constants and open positions of each quadrant
-            for i in 0..9 {
-                let pos: usize =
-                    (3 * (quadrant / 3) + (i / 3)) * 10 + (3 * (quadrant % 3) + (i % 3));
-                if self.solution[pos] == 0 {
+            //fetch constants and open positions of each region
+            for &pos in region {
+                if self.solution[pos] == EMPTY {
                     //open field for the group
                     positions.push(pos);
                 } else {
@@ -94,9 +323,17 @@ impl Puzzle {
             }
             //add a new group for the open positions
             if positions.len() > 0 {
-                let group = Group::new_sudoku(&positions, &constants);
+                let group = Group::new_sudoku(
+                    &positions,
+                    &constants,
+                    &self.digits,
+                    self.normal_group_direction,
+                );
                 if group.is_err() {
-                    return Err(format!("Quadrant with no valid options found {}", quadrant));
+                    return Err(format!(
+                        "Region with no valid options found {} in grid {}",
+                        region_index, grid
+                    ));
                 } else {
                     self.groups.push(group?);
                 }
@@ -106,24 +343,287 @@ impl Puzzle {
         Ok("ok")
     }
 
+    fn initialize_futoshiki_from_definition(
+        &mut self,
+        definition: &[String],
+    ) -> Result<&str, String> {
+        //derive field from input strings
+        for (row, row_string) in definition.iter().enumerate() {
+            self.set_row_from_definition(row, row_string)?;
+        }
+
+        self.add_latin_rows()?;
+
+        Ok("ok")
+    }
+
+    /// Sets the given digits of a row of the field from its definition,
+    /// given digits are represented by their symbols, open positions by "-".
+    /// For better readability a "." might be entered between positions.
+    fn set_row_from_definition(&mut self, row: usize, row_string: &str) -> Result<&str, String> {
+        let row_positions: Vec<char> = row_string.chars().filter(|&c| c != '.').collect();
+        if row_positions.len() != self.dimension {
+            return Err(format!("No valid row found: {}", row_string));
+        }
+
+        for (column, c) in row_positions.into_iter().enumerate() {
+            self.solution[row * 10 + column] = match c {
+                '-' => EMPTY,
+                _ => match self.get_digit(c) {
+                    Some(digit) => digit,
+                    None => return Err(format!("No valid digit '{}' in row: {}", c, row_string)),
+                },
+            };
+        }
+
+        Ok("ok")
+    }
+
+    fn initialize_skyscrapers_from_definition(
+        &mut self,
+        definition: &[String],
+    ) -> Result<&str, String> {
+        let dimension = self.dimension;
+        if self.digits != (1..=dimension).collect::<Vec<usize>>() {
+            return Err("Skyscrapers only support the digits 1 to dimension".to_string());
+        }
+
+        //the clue lines are top, bottom (both per column) and left, right (both per row)
+        for (side, clues) in definition.iter().enumerate() {
+            for (line, c) in clues.chars().enumerate() {
+                let clue = match c {
+                    '-' => continue,
+                    _ => match c.to_digit(10) {
+                        Some(clue) if clue >= 1 && clue as usize <= dimension => clue as usize,
+                        _ => return Err(format!("No valid Skyscrapers clues found: {}", clues)),
+                    },
+                };
+                //positions in viewing direction from the clue
+                let positions: Vec<usize> = match side {
+                    0 => (0..dimension).map(|row| row * 10 + line).collect(),
+                    1 => (0..dimension).rev().map(|row| row * 10 + line).collect(),
+                    2 => (0..dimension).map(|column| line * 10 + column).collect(),
+                    _ => (0..dimension).rev().map(|column| line * 10 + column).collect(),
+                };
+                self.constraints.add(Constraint::new_skyscraper(clue, positions));
+            }
+        }
+
+        self.add_latin_rows()?;
+
+        Ok("ok")
+    }
+
+    fn initialize_str8ts_from_definition(
+        &mut self,
+        definition: &[String],
+        black_positions: Vec<usize>,
+    ) -> Result<&str, String> {
+        //derive field from input strings, given digits might be white or black
+        for (row, row_string) in definition.iter().enumerate() {
+            self.set_row_from_definition(row, row_string)?;
+        }
+
+        //the compartments are the white positions between the black positions
+        //of each row and column, each compartment must form a straight
+        let dimension = self.dimension;
+        let lines: Vec<Vec<usize>> = (0..dimension)
+            .map(|row| (0..dimension).map(|column| row * 10 + column).collect())
+            .chain(
+                (0..dimension)
+                    .map(|column| (0..dimension).map(|row| row * 10 + column).collect()),
+            )
+            .collect();
+        for line in lines {
+            for compartment in line.split(|position| black_positions.contains(position)) {
+                if compartment.len() > 1 {
+                    self.constraints.add(Constraint::new_straight(compartment.to_vec()));
+                }
+            }
+        }
+        self.black_positions = black_positions;
+
+        self.add_latin_rows()?;
+
+        Ok("ok")
+    }
+
+    fn initialize_inshi_no_heya_from_definition(
+        &mut self,
+        definition: &[String],
+    ) -> Result<&str, String> {
+        //each room is given by its product and its length, e.g. "12(2)",
+        //and becomes a multiplication cage (a constant for rooms of length 1)
+        let mut cages: Vec<String> = Vec::new();
+        for (row, row_string) in definition.iter().enumerate() {
+            let mut column: usize = 0;
+            for room in row_string.split_whitespace() {
+                let product_and_length = room
+                    .strip_suffix(')')
+                    .and_then(|room| room.split_once('('))
+                    .and_then(|(product, length)| {
+                        Some((product.parse::<usize>().ok()?, length.parse::<usize>().ok()?))
+                    });
+                let (product, length) = match product_and_length {
+                    Some((product, length)) if length > 0 && column + length <= self.dimension => {
+                        (product, length)
+                    }
+                    _ => return Err(format!("No valid room '{}' in row: {}", room, row_string)),
+                };
+                let positions: Vec<String> =
+                    (column..column + length).map(|c| format!("{}{}", row, c)).collect();
+                let operation = if length == 1 { 'c' } else { '*' };
+                cages.push(format!("{}{}{}", product, operation, positions.join(".")));
+                column += length;
+            }
+            if column != self.dimension {
+                return Err(format!(
+                    "Rooms must cover all {} positions of the row: {}",
+                    self.dimension, row_string
+                ));
+            }
+        }
+
+        self.initialize_kenken_from_definition(&cages, None, &[], false)
+    }
+
+    /// Adds a group of disjunctive digits for the open (white) positions of each row,
+    /// the columns are checked via the field
+    fn add_latin_rows(&mut self) -> Result<&str, String> {
+        for row in 0..self.dimension {
+            let constants: HashSet<usize> = (0..self.dimension)
+                .map(|column| self.solution[row * 10 + column])
+                .filter(|&digit| digit != EMPTY)
+                .collect();
+            let positions: Vec<usize> = (0..self.dimension)
+                .map(|column| row * 10 + column)
+                .filter(|&position| {
+                    self.solution[position] == EMPTY && !self.black_positions.contains(&position)
+                })
+                .collect();
+
+            if !positions.is_empty() {
+                self.groups.push(Group::new_latin_row(
+                    &self.digits,
+                    &positions,
+                    &constants,
+                    self.normal_group_direction,
+                )?);
+            }
+        }
+
+        Ok("ok")
+    }
+
+    fn initialize_kakuro_from_definition(
+        &mut self,
+        definition: &[String],
+        clues: Option<&Vec<String>>,
+    ) -> Result<&str, String> {
+        //derive black and white positions from input strings
+        for (row, row_string) in definition.iter().enumerate() {
+            for (column, c) in row_string.chars().enumerate() {
+                match c {
+                    '#' => self.black_positions.push(row * 10 + column),
+                    '-' => (),
+                    _ => return Err(format!("No valid Kakuro row found: {}", row_string)),
+                }
+            }
+        }
+
+        let clues = match clues {
+            Some(clues) => clues,
+            None => return Err("Kakuro without section [Clues] found".to_string()),
+        };
+        let mut covered_positions: HashSet<usize> = HashSet::new();
+        for clue in clues {
+            let (result, clue_position, step) = self.parse_kakuro_clue(clue)?;
+
+            //the run starts next to the black clue position and ends
+            //at the next black position or the border of the puzzle
+            let positions: Vec<usize> = (1..self.dimension)
+                .map(|i| clue_position + i * step)
+                .take_while(|&p| {
+                    p / 10 < self.dimension
+                        && p % 10 < self.dimension
+                        && !self.black_positions.contains(&p)
+                })
+                .collect();
+            if positions.is_empty() {
+                return Err(format!("Kakuro clue without positions found: {}", clue));
+            }
+
+            covered_positions.extend(positions.iter());
+            self.groups.push(Group::new_kakuro(
+                &self.digits,
+                result,
+                &positions,
+                self.normal_group_direction,
+            )?);
+        }
+
+        let white_count = self.dimension * self.dimension - self.black_positions.len();
+        if covered_positions.len() != white_count {
+            return Err("Kakuro with positions not covered by any clue found".to_string());
+        }
+
+        Ok("ok")
+    }
+
+    /// Parses a Kakuro clue in the format ``` [sum][direction][position] ```,
+    /// the direction is '>' for an across run and 'v' for a down run
+    ///
+    /// Returns: the sum, the position of the clue and the step to the next position of the run
+    fn parse_kakuro_clue(&self, clue: &str) -> Result<(usize, usize, usize), String> {
+        let error = Err(format!("Can't parse Kakuro clue: {}", clue));
+
+        let direction_index = match clue.find(['>', 'v']) {
+            Some(index) => index,
+            None => return error,
+        };
+        let step = if clue[direction_index..].starts_with('>') { 1 } else { 10 };
+
+        match (
+            clue[..direction_index].trim().parse::<usize>(),
+            clue[direction_index + 1..].trim().parse::<usize>(),
+        ) {
+            (Ok(result), Ok(position)) if self.black_positions.contains(&position) => {
+                Ok((result, position, step))
+            }
+            _ => error,
+        }
+    }
+
     fn initialize_kenken_from_definition(
         &mut self,
         puzzle_string_vector: &Vec<String>,
+        givens: Option<&Vec<String>>,
+        extra_regions: &[Vec<usize>],
+        distinct_digits: bool,
     ) -> Result<&str, String> {
-        for group_as_string in puzzle_string_vector {
-            self.groups
-                .push(Group::new_kenken(self.dimension, group_as_string,self.normal_group_direction)?);
+        //the givens are pre-filled digits within the cages,
+        //one line per row with one symbol per position ('-' for an empty position)
+        if let Some(given_rows) = givens {
+            if given_rows.len() != self.dimension {
+                return Err(format!(
+                    "Givens must have {} rows.\n{:?}",
+                    self.dimension, given_rows
+                ));
+            }
+            for (row, row_string) in given_rows.iter().enumerate() {
+                self.set_row_from_definition(row, row_string)?;
+            }
         }
 
-        //initialize blocklist and apply first unique digits
-        let (o_field, c) = self.get_next_solution_step();
-
-        if let Some(of) = o_field {
-            self.solution = of.solution.clone();
-            self.block_list = of.block_list.clone();
-            self.groups = of.groups.clone();
-            self.groups.push(c.unwrap()); //add the best group to groups
+        for group_as_string in puzzle_string_vector {
+            self.groups.push(Group::new_kenken(
+                &self.digits,
+                group_as_string,
+                self.normal_group_direction,
+                distinct_digits,
+            )?);
         }
+        self.add_sudoku_regions(extra_regions, 0)?;
 
         Ok("ok")
     }
@@ -147,11 +647,17 @@ impl Puzzle {
 
         let mut min_opt: usize = 1000;
         let mut min_opt_pos: usize = 1;
+        let has_unique_lines = self.has_unique_lines();
 
         while index < new_groups.len() {
             let (opt_cnt, group_pos, valid_group) = new_groups
                 .remove(index)
-                .get_updated_group(&new_field.solution, &mut new_field.block_list);
+                .get_updated_group(
+                    &new_field.solution,
+                    &mut new_field.block_list,
+                    &new_field.constraints,
+                    has_unique_lines,
+                );
 
             match opt_cnt {
                 // no valid options left ⇒ Error and next try
@@ -189,6 +695,446 @@ impl Puzzle {
         }
     }
 
+    /// Checks if the digits of each row and column must be distinct,
+    /// which is the case for all puzzles but Kakuro
+    pub fn has_unique_lines(&self) -> bool {
+        self.game_type != Kakuro
+    }
+
+    /// Retrieves the symbol representing the given digit, "-" for EMPTY positions
+    pub fn get_symbol(&self, digit: usize) -> char {
+        match self.digits.iter().position(|&d| d == digit) {
+            Some(index) => self.symbols[index],
+            None => '-',
+        }
+    }
+
+    /// Sets the digits of the solution from the rows of a solution file,
+    /// e.g. to check a solution found by hand.
+    /// Each row holds one symbol per position, '-' for open positions and '#' for black positions
+    /// without digit. The rows of a multi grid Sudoku hold all grids at their offsets
+    /// (see get_solution_rows), cells not covered by any grid are ignored.
+    /// Digits already known from the puzzle (e.g. given digits) must not be changed.
+    ///
+    /// Returns the description of each changed digit
+    pub fn set_solution_from_rows(&mut self, rows: &[String]) -> Result<Vec<String>, String> {
+        //leading blanks only matter for the grid offsets of a multi grid Sudoku
+        let rows: Vec<Vec<char>> = if self.grid_offsets.is_empty() {
+            rows.iter().map(|row| row.trim().chars().filter(|&c| c != '.').collect()).collect()
+        } else {
+            rows.iter().map(|row| row.chars().collect()).collect()
+        };
+        if self.grid_offsets.is_empty()
+            && (rows.len() != self.dimension || rows.iter().any(|row| row.len() != self.dimension))
+        {
+            return Err(format!(
+                "The solution must have {} rows with {} positions each",
+                self.dimension, self.dimension
+            ));
+        }
+
+        //each position with the row and column of its cell within the rows
+        let cells: Vec<(usize, usize, usize)> = if self.grid_offsets.is_empty() {
+            (0..self.dimension)
+                .flat_map(|row| (0..self.dimension).map(move |column| (row * 10 + column, row, column)))
+                .collect()
+        } else {
+            self.grid_offsets
+                .iter()
+                .enumerate()
+                .flat_map(|(grid, &(row_offset, column_offset))| {
+                    (0..81).map(move |i| {
+                        (grid * 100 + (i / 9) * 10 + i % 9, row_offset + i / 9, column_offset + i % 9)
+                    })
+                })
+                .collect()
+        };
+
+        //the solution is only changed, if all rows are valid
+        let known_solution = self.solution.clone();
+        let mut solution = self.solution.clone();
+        for (position, row, column) in cells {
+            let symbol = match rows.get(row).and_then(|row| row.get(column)) {
+                Some(&symbol) => symbol,
+                None => return Err(format!("Position {:02} is missing in the rows of the solution", position)),
+            };
+            let is_black = self.black_positions.contains(&position);
+            solution[position] = match symbol {
+                '-' => EMPTY,
+                '#' if is_black => EMPTY,
+                _ => match self.get_digit(symbol) {
+                    //only the given digits of a Str8ts are placed on black positions
+                    Some(digit) if !is_black || known_solution[position] != EMPTY => digit,
+                    _ => {
+                        return Err(format!(
+                            "No valid symbol '{}' at position {:02} of the solution",
+                            symbol, position
+                        ))
+                    }
+                },
+            };
+        }
+        self.solution = solution;
+
+        Ok((0..self.solution.len())
+            .filter(|&p| known_solution[p] != EMPTY && known_solution[p] != self.solution[p])
+            .map(|p| {
+                format!(
+                    "Position {:02} must hold {}, found {}",
+                    p,
+                    self.get_symbol(known_solution[p]),
+                    self.get_symbol(self.solution[p])
+                )
+            })
+            .collect())
+    }
+
+    /// Checks the current solution against the rules of the puzzle, i.e.
+    ///  * distinct digits in each row and column (if required by the puzzle)
+    ///  * the options of the undecided groups
+    ///  * the constraints, e.g. inequalities or the chess rules
+    ///
+    /// Returns the description of each violation, an empty list for a valid solution
+    pub fn get_violations(&self) -> Vec<String> {
+        let mut violations: Vec<String> = Vec::new();
+        let dimension = self.dimension;
+
+        if self.has_unique_lines() {
+            for grid in 0..self.grid_offsets.len().max(1) {
+                for line in 0..dimension {
+                    let row: Vec<usize> = (0..dimension).map(|i| grid * 100 + line * 10 + i).collect();
+                    let column: Vec<usize> = (0..dimension).map(|i| grid * 100 + i * 10 + line).collect();
+                    for (name, positions) in [("row", row), ("column", column)] {
+                        let mut digits: Vec<usize> = positions
+                            .iter()
+                            .map(|&p| self.solution[p])
+                            .filter(|&digit| digit != EMPTY)
+                            .collect();
+                        digits.sort_unstable();
+                        if digits.windows(2).any(|pair| pair[0] == pair[1]) {
+                            violations.push(format!(
+                                "Repeated digits in {} starting at position {:02}",
+                                name, positions[0]
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        for group in self.groups.iter() {
+            let digits: Vec<usize> = group.positions().iter().map(|&p| self.solution[p]).collect();
+            if !group.options().contains(&digits) {
+                //groups of disjunctive digits have no result, all other groups are named as cage
+                violations.push(match group.operation() {
+                    's' => format!(
+                        "Group of disjunctive digits not fulfilled at positions {:?}",
+                        group.positions()
+                    ),
+                    _ => format!("Group {} not fulfilled", group.to_cage_string()),
+                });
+            }
+        }
+
+        for constraint in self.constraints.get_violations(&self.solution) {
+            violations.push(format!(
+                "Constraint '{}' not fulfilled at positions {:?}",
+                constraint.relation(),
+                constraint.positions()
+            ));
+        }
+
+        violations
+    }
+
+    /// Retrieves the digit represented by the given symbol, None for unknown symbols
+    pub fn get_digit(&self, symbol: char) -> Option<usize> {
+        self.symbols
+            .iter()
+            .position(|&s| s == symbol)
+            .map(|index| self.digits[index])
+    }
+
+    /// Prints the grids of a multi grid Sudoku with the digits of the given field at their offsets,
+    /// cells not covered by any grid are printed as " "
+    fn get_multi_grid_display(&self, field: &[usize]) -> String {
+        let height = self.grid_offsets.iter().map(|&(row, _)| row + 9).max().unwrap_or(0);
+        let width = self.grid_offsets.iter().map(|&(_, column)| column + 9).max().unwrap_or(0);
+
+        (0..height)
+            .map(|row| {
+                (0..width)
+                    .map(|column| {
+                        self.grid_offsets
+                            .iter()
+                            .enumerate()
+                            .find(|(_, &(row_offset, column_offset))| {
+                                (row_offset..row_offset + 9).contains(&row)
+                                    && (column_offset..column_offset + 9).contains(&column)
+                            })
+                            .map_or(' ', |(grid, &(row_offset, column_offset))| {
+                                self.get_symbol(
+                                    field[grid * 100 + (row - row_offset) * 10 + column - column_offset],
+                                )
+                            })
+                    })
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
+    }
+
+    /// Checks if the current solution equals the expected solution given as rows,
+    /// separators between the rows (whitespace and ',') are ignored
+    pub fn matches_solution(&self, expected_solution: &str) -> bool {
+        let to_symbols =
+            |rows: &str| -> String { rows.chars().filter(|c| !c.is_whitespace() && *c != ',').collect() };
+        to_symbols(expected_solution) == to_symbols(&self.get_solution_rows().concat())
+    }
+
+    /// Retrieves the rows of the current solution with the symbols of the digits,
+    /// '-' for open positions and '#' for black positions without digit
+    pub fn get_solution_rows(&self) -> Vec<String> {
+        self.get_rows(&self.solution)
+    }
+
+    /// Retrieves the rows of the given field (the solution or the given digits)
+    /// with the symbols of the digits, '-' for open positions and '#' for black positions without digit
+    fn get_rows(&self, field: &[usize]) -> Vec<String> {
+        if !self.grid_offsets.is_empty() {
+            return self.get_multi_grid_display(field).lines().map(|row| row.to_string()).collect();
+        }
+
+        (0..self.dimension)
+            .map(|row| {
+                (row * 10..row * 10 + self.dimension)
+                    .map(|position| {
+                        if self.black_positions.contains(&position) && field[position] == EMPTY {
+                            '#'
+                        } else {
+                            self.get_symbol(field[position])
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Converts the puzzle back to a puzzle file, e.g. to save a programmatically edited puzzle,
+    /// which is loaded to an equal puzzle. The puzzle file is built from
+    ///  * the given digits (not the current solution), the black positions and the grids
+    ///  * the groups, i.e. the cages of a KenKen, the runs of a Kakuro or the rooms of an Inshi no heya
+    ///  * the constraints, e.g. the inequalities, the clues of a Skyscrapers or the parity map
+    ///  * the regions, the digits, the symbols, the rules, the description and the headers
+    ///
+    /// A solved puzzle (see solve) has no groups left, hence it is saved without cages, runs or rooms.
+    pub fn to_puzzle_string(&self) -> PuzzleAsString {
+        let dimension = self.dimension;
+        let mut sections: Vec<(String, Vec<String>)> = Vec::new();
+        let positions_string = |positions: &[usize]| -> String {
+            positions.iter().map(|p| format!("{:02}", p)).collect::<Vec<String>>().join(".")
+        };
+        let map_rows = |mark: &dyn Fn(usize) -> char| -> Vec<String> {
+            (0..dimension)
+                .map(|row| (0..dimension).map(|column| mark(row * 10 + column)).collect())
+                .collect()
+        };
+
+        let puzzle_lines: Vec<String> = match self.game_type {
+            //the distinct digits of all cages are given by the rule DistinctCages
+            KenKen => self
+                .groups
+                .iter()
+                .filter(|group| *group.operation() != 's')
+                .map(|group| group.to_cage_string())
+                .map(|cage| match self.setup.rules.contains(&Rule::DistinctCages) {
+                    true => cage.trim_start_matches('!').to_string(),
+                    false => cage,
+                })
+                .collect(),
+            //the rows of a multi grid Sudoku mark the cells outside of the grids by '#'
+            MultiSudoku => self.get_rows(&self.setup.givens).iter().map(|row| row.replace(' ', "#")).collect(),
+            //the black positions of a Str8ts are given by the section "Black"
+            Str8ts => self.get_rows(&self.setup.givens).iter().map(|row| row.replace('#', "-")).collect(),
+            Sudoku | Futoshiki | Kakuro => self.get_rows(&self.setup.givens),
+            //the rooms of each row ordered by their first column, e.g. "12(2) 3(1)"
+            InshiNoHeya => (0..dimension)
+                .map(|row| {
+                    let mut rooms: Vec<&Group> =
+                        self.groups.iter().filter(|group| group.positions()[0] / 10 == row).collect();
+                    rooms.sort_by_key(|group| group.positions()[0]);
+                    rooms
+                        .iter()
+                        .map(|group| format!("{}({})", group.result(), group.positions().len()))
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect(),
+            //the clues top, bottom (both per column) and left, right (both per row)
+            //are given by the first two positions of each view
+            Skyscrapers => {
+                let mut clue_lines: Vec<Vec<char>> = vec![vec!['-'; dimension]; 4];
+                for constraint in self.constraints.iter().filter(|c| *c.relation() == 'v') {
+                    let (first, second) = (constraint.positions()[0], constraint.positions()[1]);
+                    let (side, line) = match second as isize - first as isize {
+                        10 => (0, first % 10),
+                        -10 => (1, first % 10),
+                        1 => (2, first / 10),
+                        _ => (3, first / 10),
+                    };
+                    clue_lines[side][line] =
+                        char::from_digit(*constraint.result() as u32, 10).unwrap_or('-');
+                }
+                clue_lines.iter().map(|clue_line| clue_line.iter().collect()).collect()
+            }
+        };
+
+        //a Kakuro clue is placed at the black position before its run,
+        //a run of length 1 is written as across run, if possible
+        if self.game_type == Kakuro {
+            let clues: Vec<String> = self
+                .groups
+                .iter()
+                .map(|group| {
+                    let first = group.positions()[0];
+                    let step = match group.positions().get(1) {
+                        Some(second) => second - first,
+                        None if first % 10 > 0 && self.black_positions.contains(&(first - 1)) => 1,
+                        None => 10,
+                    };
+                    let direction = if step == 1 { '>' } else { 'v' };
+                    format!("{}{}{:02}", group.result(), direction, first - step)
+                })
+                .collect();
+            sections.push(("Clues".to_string(), clues));
+        }
+        if self.game_type == Str8ts {
+            let black_map = map_rows(&|position| match self.black_positions.contains(&position) {
+                true => '#',
+                false => '-',
+            });
+            sections.push(("Black".to_string(), black_map));
+        }
+        if self.game_type == KenKen && self.setup.givens.iter().any(|&digit| digit != EMPTY) {
+            sections.push(("Givens".to_string(), self.get_rows(&self.setup.givens)));
+        }
+        if self.game_type == MultiSudoku {
+            let grid_lines = self.grid_offsets.iter().map(|(row, column)| format!("{} {}", row, column));
+            sections.push(("Grids".to_string(), grid_lines.collect()));
+        }
+
+        //a region map is only needed for other regions than the 3x3 quadrants,
+        //the regions are named by the digits 1 to 9
+        let is_quadrant = |region: &Vec<usize>| -> bool {
+            region.iter().all(|&p| (p / 30, p % 10 / 3) == (region[0] / 30, region[0] % 10 / 3))
+        };
+        if !self.setup.regions.iter().all(is_quadrant) {
+            let region_map = map_rows(&|position| {
+                let region_index = self.setup.regions.iter().position(|region| region.contains(&position));
+                region_index.map_or('-', |index| char::from_digit(index as u32 + 1, 10).unwrap_or('-'))
+            });
+            sections.push(("Regions".to_string(), region_map));
+        }
+        if !self.setup.extra_regions.is_empty() {
+            let region_lines = self.setup.extra_regions.iter().map(|region| positions_string(region));
+            sections.push(("Extra Regions".to_string(), region_lines.collect()));
+        }
+
+        //the constraints of the rules (e.g. AntiKnight), of the shared cells of the grids,
+        //of the Skyscrapers clues and of the Str8ts compartments are derived when loading
+        let mut constraint_sections: Vec<(&str, Vec<String>)> = [
+            "Inequalities",
+            "Adjacent",
+            "Sandwiches",
+            "Thermometers",
+            "Arrows",
+        ]
+        .iter()
+        .map(|&name| (name, Vec::new()))
+        .collect();
+        let mut parity_positions: Vec<(usize, usize)> = Vec::new();
+        for constraint in self.constraints.iter() {
+            let positions = constraint.positions();
+            let (section_index, line) = match constraint.relation() {
+                '<' => (0, format!("{:02}<{:02}", positions[0], positions[1])),
+                'w' | 'b' | 'X' | 'V' => (
+                    1,
+                    format!("{:02}{}{:02}", positions[0], constraint.relation(), positions[1]),
+                ),
+                'S' if positions[0] / 10 == positions[1] / 10 => {
+                    (2, format!("r{}={}", positions[0] / 10, constraint.result()))
+                }
+                'S' => (2, format!("c{}={}", positions[0] % 10, constraint.result())),
+                't' => (3, positions_string(positions)),
+                'a' => (4, positions_string(positions)),
+                'p' => {
+                    parity_positions.push((positions[0], *constraint.result()));
+                    continue;
+                }
+                _ => continue,
+            };
+            constraint_sections[section_index].1.push(line);
+        }
+        for (name, lines) in constraint_sections.into_iter().filter(|(_, lines)| !lines.is_empty()) {
+            sections.push((name.to_string(), lines));
+        }
+        if !parity_positions.is_empty() {
+            let parity_map = map_rows(&|position| {
+                match parity_positions.iter().find(|&&(p, _)| p == position) {
+                    Some((_, 0)) => 'e',
+                    Some(_) => 'o',
+                    None => '-',
+                }
+            });
+            sections.push(("Parity".to_string(), parity_map));
+        }
+
+        //the digits 1 to dimension (1 to 9 for Kakuro) and their own symbols are the default
+        let digit_count = if self.game_type == Kakuro { 9 } else { dimension };
+        if self.digits != (1..=digit_count).collect::<Vec<usize>>() {
+            let digit_line: String =
+                self.digits.iter().filter_map(|&d| char::from_digit(d as u32, 10)).collect();
+            sections.push(("Digits".to_string(), vec![digit_line]));
+        }
+        let digit_symbols: Vec<char> =
+            self.digits.iter().filter_map(|&d| char::from_digit(d as u32, 10)).collect();
+        if self.symbols != digit_symbols {
+            sections.push(("Symbols".to_string(), vec![self.symbols.iter().collect()]));
+        }
+
+        PuzzleAsString::new(
+            self.setup.description.clone(),
+            self.setup.metadata.clone(),
+            self.game_type,
+            self.setup.rules.clone(),
+            self.normal_group_direction,
+            puzzle_lines,
+            sections,
+        )
+    }
+
+    /// Converts the puzzle to the text of a puzzle file (see to_puzzle_string)
+    pub fn to_raw_string(&self) -> String {
+        self.to_puzzle_string().to_raw_string()
+    }
+
+    /// Converts the puzzle to a JSON object in the format of JsonPuzzle (see to_puzzle_string),
+    /// the internal state of the solver, e.g. the options of the groups, isn't part of it
+    pub fn to_json(&self) -> Result<String, String> {
+        self.to_puzzle_string().to_json()
+    }
+
+    /// Converts the current solution to a JSON object with the game type, the dimension
+    /// and the rows of the solution (see get_solution_rows)
+    pub fn solution_to_json(&self) -> String {
+        serde_json::json!({
+            "game_type": self.game_type,
+            "dimension": self.dimension,
+            "rows": self.get_solution_rows(),
+        })
+        .to_string()
+    }
+
     pub fn set_option_for_group(&mut self, group: &Group, option_index: usize) {
         group.apply_option_to_field(&mut self.solution, option_index)
     }
@@ -232,21 +1178,12 @@ impl Puzzle {
 }
 
 /// Implementation of the format trait for the puzzle
-/// The field is printed as a dimension x dimension matrix
+/// The field is printed as a dimension x dimension matrix of the digit symbols,
+/// black positions without a digit are printed as "#".
+/// Multi grid Sudokus are printed with all grids at their offsets.
 impl fmt::Display for Puzzle {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let dimension = self.dimension;
-        let display: String = (0..89)
-            .map(|index| {
-                if (index % 10) < dimension && (index / 10) < dimension {
-                    self.solution[index].to_string()
-                } else if (index % 10) == dimension && (index / 10) < dimension {
-                    "\n".to_string()
-                } else {
-                    "".to_string()
-                }
-            })
-            .collect();
+        let display: String = self.get_solution_rows().iter().map(|row| row.clone() + "\n").collect();
 
         write!(f, "{}", display.blue())
     }
@@ -257,6 +1194,7 @@ mod kk_group_tests {
     use crate::kk_load::GameType::KenKen;
 
     use super::*;
+    use std::fs;
 
     #[test]
     //checks the functions
@@ -281,8 +1219,8 @@ mod kk_group_tests {
         //check apply option_to field
         let group = kenken.groups.remove(1);
         kenken.set_option_for_group(&group, 0);
-        assert_eq!(kenken.solution[0], 0);
-        assert_eq!(kenken.solution[33], 0);
+        assert_eq!(kenken.solution[0], EMPTY);
+        assert_eq!(kenken.solution[33], EMPTY);
         assert_eq!(kenken.solution[2], 1);
         assert_eq!(kenken.solution[3], 3);
         assert_eq!(kenken.solution[12], 4);
@@ -316,7 +1254,7 @@ mod kk_group_tests {
             .unwrap()
             .solution
             .into_iter()
-            .filter(|&d| d > 0)
+            .filter(|&d| d != EMPTY)
             .collect();
 
         let manual_solution: Vec<usize> = "2341123434124123"
@@ -327,6 +1265,28 @@ mod kk_group_tests {
         assert_eq!(found_solution, manual_solution);
     }
 
+    #[test]
+    //checks comparing the solution with the header "expected solution" (and its alias "solution")
+    fn check_expected_solution() {
+        let kenken_file = std::fs::read_to_string("KK-Dim4-1.txt").unwrap();
+        for (header, matches) in [
+            ("expected solution: 2341,1234,3412,4123", true),
+            ("Expected Solution: 2341 1234 3412 4123", true),
+            ("solution: 2341,1234,3412,4123", true),
+            ("expected solution: 2341,1234,4312,3124", false),
+        ] {
+            let (description, kenken) = kenken_file.split_once('\n').unwrap();
+            let kenken_string = PuzzleAsString::new_from_raw_string(
+                format!("{}\n{}\n{}", description, header, kenken),
+                true,
+            )
+            .unwrap();
+            let expected_solution = kenken_string.get_expected_solution().unwrap().clone();
+            let solution = Puzzle::new_from_puzzle_file(kenken_string).unwrap().solve().unwrap();
+            assert_eq!(solution.matches_solution(&expected_solution), matches, "{}", header);
+        }
+    }
+
     #[test]
     //checks the functions
     // * new_from_puzzle_file,
@@ -344,7 +1304,7 @@ mod kk_group_tests {
         let kenken = kenken_option.unwrap();
         assert_eq!(kenken.game_type, KenKen);
         assert_eq!(kenken.dimension, 9);
-        assert_eq!(kenken.groups.len(), 28);
+        assert_eq!(kenken.groups.len(), 32);
         assert_eq!(kenken.solution.len(), 90);
 
         let solution_option = kenken.solve();
@@ -354,7 +1314,7 @@ mod kk_group_tests {
         let found_solution: Vec<usize> = solution
             .solution
             .iter()
-            .filter(|&d| d > &0)
+            .filter(|&d| d != &EMPTY)
             .map(|&d| d)
             .collect();
 
@@ -366,4 +1326,640 @@ mod kk_group_tests {
 
         assert_eq!(found_solution, manual_solution);
     }
+
+    #[test]
+    //checks solving a jigsaw sudoku with irregular regions from the file S-Jigsaw-1.txt
+    fn check_jigsaw_sudoku_solve() {
+        let sudoku_as_string = PuzzleAsString::new_from_file("S-Jigsaw-1.txt").unwrap();
+
+        let sudoku = Puzzle::new_from_puzzle_file(sudoku_as_string).unwrap();
+        assert_eq!(sudoku.game_type, Sudoku);
+        assert_eq!(sudoku.groups.len(), 9);
+
+        let found_solution: Vec<usize> = sudoku
+            .solve()
+            .unwrap()
+            .solution
+            .into_iter()
+            .filter(|&d| d != EMPTY)
+            .collect();
+
+        let manual_solution: Vec<usize> =
+            "694812753315487269527963148759138426836724591461295387273659814982341675148576932"
+                .chars()
+                .map(|c| c.to_digit(10).unwrap() as usize)
+                .collect();
+
+        assert_eq!(found_solution, manual_solution);
+    }
+
+    #[test]
+    fn check_hyper_sudoku_solve() {
+        let sudoku_as_string = PuzzleAsString::new_from_file("S-Hyper-1.txt").unwrap();
+
+        let sudoku = Puzzle::new_from_puzzle_file(sudoku_as_string).unwrap();
+        assert_eq!(sudoku.groups.len(), 13);
+
+        let found_solution: Vec<usize> = sudoku
+            .solve()
+            .unwrap()
+            .solution
+            .into_iter()
+            .filter(|&d| d != EMPTY)
+            .collect();
+
+        let manual_solution: Vec<usize> =
+            "249368715356971824871425639583219476462783951197654382734592168628147593915836247"
+                .chars()
+                .map(|c| c.to_digit(10).unwrap() as usize)
+                .collect();
+
+        assert_eq!(found_solution, manual_solution);
+    }
+
+    #[test]
+    fn check_kenken_with_extra_regions() {
+        //without the extra region, two latin squares fit the givens
+        let raw_kenken = "KenKen with extra region\nKenKen\n6+00.01.02\n6+10.11.12\n6+20.21.22\n\
+                          [Givens]\n12-\n---\n---\n[Extra Regions]\n02.11.20"
+            .to_string();
+
+        for normal_group_direction in [true, false] {
+            let kenken_as_string =
+                PuzzleAsString::new_from_raw_string(raw_kenken.clone(), normal_group_direction)
+                    .unwrap();
+            let found_solution: Vec<usize> = Puzzle::new_from_puzzle_file(kenken_as_string)
+                .unwrap()
+                .solve()
+                .unwrap()
+                .solution
+                .into_iter()
+                .filter(|&d| d != EMPTY)
+                .collect();
+            assert_eq!(found_solution, vec!(1, 2, 3, 3, 1, 2, 2, 3, 1));
+        }
+
+        for invalid_region in ["02.11.30", "02.11.11", "02.1.20", "00.01.02.11"] {
+            let invalid_kenken = PuzzleAsString::new_from_raw_string(
+                raw_kenken.replace("02.11.20", invalid_region),
+                true,
+            )
+            .and_then(Puzzle::new_from_puzzle_file);
+            assert!(invalid_kenken.is_err());
+        }
+    }
+
+    #[test]
+    fn check_json_serialization() {
+        let kenken_as_string = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
+        let kenken = Puzzle::new_from_puzzle_file(kenken_as_string).unwrap();
+        let kenken_json = kenken.to_json().unwrap();
+        assert!(kenken_json.contains(r#""game_type":"KenKen","#));
+        assert!(kenken_json.contains(r#""dimension":4,"#));
+        assert!(kenken_json.contains(r#""cages":[{"result":"#));
+        assert!(!kenken_json.contains("options"));
+        assert!(!kenken_json.contains(&usize::MAX.to_string()));
+        let reloaded = PuzzleAsString::new_from_json(&kenken_json, true).unwrap();
+        assert_eq!(reloaded, kenken.to_puzzle_string());
+
+        let solution = kenken.solve().unwrap();
+        assert_eq!(solution.get_solution_rows(), vec!("2341", "1234", "3412", "4123"));
+        assert_eq!(
+            solution.solution_to_json(),
+            r#"{"dimension":4,"game_type":"KenKen","rows":["2341","1234","3412","4123"]}"#
+        );
+    }
+
+    #[test]
+    fn check_text_serialization() {
+        //saving and reloading keeps each puzzle, i.e. its given digits, groups, sections, rules
+        //and metadata, the saved text is saved again unchanged
+        let with_sections = |file_name: &str, sections: &str| -> String {
+            fs::read_to_string(file_name).unwrap().trim_end().to_string() + sections
+        };
+        let mut raw_puzzles: Vec<String> = [
+            "KK-Dim4-1.txt",
+            "KK-Dim9-1.txt",
+            "KK-Map-1.txt",
+            "S-1.txt",
+            "S-Jigsaw-1.txt",
+            "S-Hyper-1.txt",
+            "S-AntiKnight-1.txt",
+            "F-1.txt",
+            "SK-1.txt",
+            "KA-1.txt",
+            "ST-1.txt",
+            "IN-1.txt",
+            "MS-Twin-1.txt",
+            "MS-Samurai-1.txt",
+        ]
+        .iter()
+        .map(|file_name| fs::read_to_string(file_name).unwrap())
+        .collect();
+        raw_puzzles.push(with_sections(
+            "KK-Dim4-1.txt",
+            "\n[Extra Regions]\n00.11.22.33\n[Givens]\nB---\n----\n----\n---C\n[Symbols]\nABCD\n",
+        ));
+        raw_puzzles.push(with_sections(
+            "S-1.txt",
+            "\n[Parity]\n-o-------\n---------\n---------\n---------\n---------\n\
+             ---------\n---------\n---------\n---------\n[Thermometers]\n30.31.32\n\
+             [Arrows]\n55.65.75\n[Adjacent]\n00w10\n[Sandwiches]\nc8=10\n",
+        ));
+        for raw_puzzle in raw_puzzles {
+            let puzzle_as_string = PuzzleAsString::new_from_raw_string(raw_puzzle.clone(), true).unwrap();
+            let puzzle = Puzzle::new_from_puzzle_file(puzzle_as_string.clone()).unwrap();
+            let saved = puzzle.to_raw_string();
+            let reloaded_as_string = PuzzleAsString::new_from_raw_string(saved.clone(), true).unwrap();
+            assert_eq!(reloaded_as_string.description(), puzzle_as_string.description());
+            assert_eq!(reloaded_as_string.metadata(), puzzle_as_string.metadata());
+            let reloaded = Puzzle::new_from_puzzle_file(reloaded_as_string).unwrap();
+            assert_eq!(reloaded.to_raw_string(), saved, "{}", raw_puzzle);
+            assert_eq!(reloaded.setup.givens, puzzle.setup.givens, "{}", saved);
+            let group_strings =
+                |puzzle: &Puzzle| puzzle.groups.iter().map(|group| group.to_cage_string()).collect::<Vec<String>>();
+            assert_eq!(group_strings(&reloaded), group_strings(&puzzle), "{}", saved);
+        }
+
+        //the sections are written from the constraints, the givens and the regions
+        let kenken_as_string = PuzzleAsString::new_from_raw_string(
+            with_sections("KK-Dim4-1.txt", "\n[Extra Regions]\n00.11.22.33\n[Givens]\n2---\n----\n----\n---3\n"),
+            true,
+        )
+        .unwrap();
+        let saved = Puzzle::new_from_puzzle_file(kenken_as_string).unwrap().to_raw_string();
+        assert!(saved.contains("\n[Givens]\n2---\n----\n----\n---3\n"));
+        assert!(saved.contains("\n[Extra Regions]\n00.11.22.33\n"));
+        let jigsaw_as_string = PuzzleAsString::new_from_file("S-Jigsaw-1.txt").unwrap();
+        let saved = Puzzle::new_from_puzzle_file(jigsaw_as_string.clone()).unwrap().to_raw_string();
+        let saved_as_string = PuzzleAsString::new_from_raw_string(saved, true).unwrap();
+        assert_eq!(saved_as_string.get_regions(), jigsaw_as_string.get_regions());
+
+        //a solved Sudoku is saved with its given digits, not with its solution
+        let sudoku_as_string = PuzzleAsString::new_from_file("S-1.txt").unwrap();
+        let sudoku = Puzzle::new_from_puzzle_file(sudoku_as_string.clone()).unwrap();
+        let solved_sudoku = sudoku.solve().unwrap();
+        assert_eq!(solved_sudoku.to_raw_string(), sudoku.to_raw_string());
+        let saved_sudoku = PuzzleAsString::new_from_raw_string(solved_sudoku.to_raw_string(), true).unwrap();
+        let given_rows: Vec<String> =
+            sudoku_as_string.puzzle_string().iter().map(|row| row.replace('.', "")).collect();
+        assert_eq!(saved_sudoku.puzzle_string(), &given_rows);
+
+        //a puzzle can be moved to another thread, e.g. to solve it in the background
+        fn assert_send<T: Send>() {}
+        assert_send::<Puzzle>();
+
+        //edited groups of a KenKen are saved as cage lines
+        let kenken_as_string = PuzzleAsString::new_from_file("KK-Dim4-1.txt").unwrap();
+        let mut kenken = Puzzle::new_from_puzzle_file(kenken_as_string.clone()).unwrap();
+        assert_eq!(kenken.groups[0].to_cage_string(), "1-00.01");
+        kenken.groups[0] = Group::new_kenken(&kenken.digits, "!5+00.01", true, false).unwrap();
+        let edited_kenken = PuzzleAsString::new_from_raw_string(kenken.to_raw_string(), true).unwrap();
+        assert_eq!(edited_kenken.puzzle_string()[0], "!5+00.01");
+        assert_eq!(edited_kenken.puzzle_string()[1..], kenken_as_string.puzzle_string()[1..]);
+        let solution = Puzzle::new_from_puzzle_file(edited_kenken).unwrap().solve().unwrap();
+        assert_eq!(solution.get_solution_rows(), vec!("2341", "1234", "3412", "4123"));
+    }
+
+    #[test]
+    //checks solving a futoshiki with inequalities from the file F-1.txt
+    fn check_futoshiki_solve() {
+        let futoshiki_as_string = PuzzleAsString::new_from_file("F-1.txt").unwrap();
+
+        let futoshiki = Puzzle::new_from_puzzle_file(futoshiki_as_string).unwrap();
+        assert_eq!(futoshiki.game_type, Futoshiki);
+        assert_eq!(futoshiki.dimension, 5);
+        assert_eq!(futoshiki.groups.len(), 5);
+
+        let found_solution: Vec<usize> = futoshiki
+            .solve()
+            .unwrap()
+            .solution
+            .into_iter()
+            .filter(|&d| d != EMPTY)
+            .collect();
+
+        let manual_solution: Vec<usize> = "5342145213215341435232145"
+            .chars()
+            .map(|c| c.to_digit(10).unwrap() as usize)
+            .collect();
+
+        assert_eq!(found_solution, manual_solution);
+    }
+
+    #[test]
+    //checks solving a skyscrapers puzzle with clues from the file SK-1.txt
+    fn check_skyscrapers_solve() {
+        let skyscrapers_as_string = PuzzleAsString::new_from_file("SK-1.txt").unwrap();
+
+        let skyscrapers = Puzzle::new_from_puzzle_file(skyscrapers_as_string).unwrap();
+        assert_eq!(skyscrapers.game_type, Skyscrapers);
+        assert_eq!(skyscrapers.dimension, 5);
+        assert_eq!(skyscrapers.groups.len(), 5);
+
+        let found_solution: Vec<usize> = skyscrapers
+            .solve()
+            .unwrap()
+            .solution
+            .into_iter()
+            .filter(|&d| d != EMPTY)
+            .collect();
+
+        let manual_solution: Vec<usize> = "1253424153312454532153412"
+            .chars()
+            .map(|c| c.to_digit(10).unwrap() as usize)
+            .collect();
+
+        assert_eq!(found_solution, manual_solution);
+    }
+
+    #[test]
+    //checks that inequalities restrict the solution of a kenken
+    fn check_kenken_with_inequalities() {
+        //without the inequalities, each row could be any permutation of 1,2,3
+        let raw_kenken = "KenKen with inequalities\nKenKen\n6+00.01.02\n6+10.11.12\n6+20.21.22\n\
+                          [Inequalities]\n00<01\n01<02\n10>11"
+            .to_string();
+
+        for normal_group_direction in [true, false] {
+            let kenken_as_string =
+                PuzzleAsString::new_from_raw_string(raw_kenken.clone(), normal_group_direction)
+                    .unwrap();
+            let found_solution: Vec<usize> = Puzzle::new_from_puzzle_file(kenken_as_string)
+                .unwrap()
+                .solve()
+                .unwrap()
+                .solution
+                .into_iter()
+                .filter(|&d| d != EMPTY)
+                .collect();
+            assert_eq!(found_solution, vec!(1, 2, 3, 3, 1, 2, 2, 3, 1));
+        }
+    }
+
+    #[test]
+    fn check_kenken_with_adjacent_clues() {
+        //without the clues, each row could be any permutation of 1,2,3
+        let raw_kenken = "KenKen with adjacent clues\nKenKen\n6+00.01.02\n6+10.11.12\n6+20.21.22\n\
+                          [Adjacent]\n00b01\n01V02\n10w11"
+            .to_string();
+
+        for normal_group_direction in [true, false] {
+            let kenken_as_string =
+                PuzzleAsString::new_from_raw_string(raw_kenken.clone(), normal_group_direction)
+                    .unwrap();
+            let found_solution: Vec<usize> = Puzzle::new_from_puzzle_file(kenken_as_string)
+                .unwrap()
+                .solve()
+                .unwrap()
+                .solution
+                .into_iter()
+                .filter(|&d| d != EMPTY)
+                .collect();
+            assert_eq!(found_solution, vec!(1, 2, 3, 2, 3, 1, 3, 1, 2));
+        }
+    }
+
+    #[test]
+    fn check_kenken_with_sandwich_thermometer_arrow() {
+        //without the clues, the puzzle is any latin square
+        let raw_kenken = "KenKen with line clues\nKenKen\n10+00.01.02.03\n10+10.11.12.13\n\
+                          10+20.21.22.23\n10+30.31.32.33\n\
+                          [Sandwiches]\nr2=5\nc2=0\n[Thermometers]\n22.13.02\n[Arrows]\n21.30.31"
+            .to_string();
+
+        for normal_group_direction in [true, false] {
+            let kenken_as_string =
+                PuzzleAsString::new_from_raw_string(raw_kenken.clone(), normal_group_direction)
+                    .unwrap();
+            let found_solution: Vec<usize> = Puzzle::new_from_puzzle_file(kenken_as_string)
+                .unwrap()
+                .solve()
+                .unwrap()
+                .solution
+                .into_iter()
+                .filter(|&d| d != EMPTY)
+                .collect();
+            assert_eq!(
+                found_solution,
+                vec!(3, 1, 4, 2, 2, 4, 1, 3, 4, 3, 2, 1, 1, 2, 3, 4)
+            );
+        }
+    }
+
+    #[test]
+    fn check_kenken_with_parity_map() {
+        //without the parity map, three of the latin squares fulfill the inequality
+        let raw_kenken = "KenKen with parity map\nKenKen\n6+00.01.02\n6+10.11.12\n6+20.21.22\n\
+                          [Inequalities]\n00<01\n[Parity]\n-e-\ne--\n---"
+            .to_string();
+
+        for normal_group_direction in [true, false] {
+            let kenken_as_string =
+                PuzzleAsString::new_from_raw_string(raw_kenken.clone(), normal_group_direction)
+                    .unwrap();
+            let found_solution: Vec<usize> = Puzzle::new_from_puzzle_file(kenken_as_string)
+                .unwrap()
+                .solve()
+                .unwrap()
+                .solution
+                .into_iter()
+                .filter(|&d| d != EMPTY)
+                .collect();
+            assert_eq!(found_solution, vec!(1, 2, 3, 2, 3, 1, 3, 1, 2));
+        }
+
+        let invalid_kenken = PuzzleAsString::new_from_raw_string(
+            raw_kenken.replace("-e-", "-x-"),
+            true,
+        )
+        .and_then(Puzzle::new_from_puzzle_file);
+        assert!(invalid_kenken.is_err());
+
+        //a solution of the KenKen without the parity map violates the shaded position 01
+        let kenken_as_string = PuzzleAsString::new_from_raw_string(raw_kenken.clone(), true).unwrap();
+        let mut kenken = Puzzle::new_from_puzzle_file(kenken_as_string).unwrap();
+        let odd_digits: HashSet<usize> = vec![1, 3].into_iter().collect();
+        assert_eq!(kenken.block_list.get_position_block_list(&1), odd_digits);
+        assert_eq!(kenken.block_list.get_position_block_list(&10), odd_digits);
+        assert!(kenken.block_list.get_position_block_list(&0).is_empty());
+        let rows: Vec<String> = ["132", "213", "321"].iter().map(|row| row.to_string()).collect();
+        kenken.set_solution_from_rows(&rows).unwrap();
+        assert_eq!(
+            kenken.get_violations(),
+            vec!("Constraint 'p' not fulfilled at positions [1]")
+        );
+
+        //the given 5 at position 01 of the Sudoku contradicts the shaded even position
+        let raw_sudoku = std::fs::read_to_string("S-1.txt").unwrap()
+            + "\n[Parity]\n-e-------\n---------\n---------\n---------\n---------\n\
+               ---------\n---------\n---------\n---------\n";
+        let sudoku_as_string = PuzzleAsString::new_from_raw_string(raw_sudoku.clone(), true).unwrap();
+        assert_eq!(
+            Puzzle::new_from_puzzle_file(sudoku_as_string).unwrap_err(),
+            "Given digits contradict the constraint 'p' at positions [1]"
+        );
+        let sudoku_as_string =
+            PuzzleAsString::new_from_raw_string(raw_sudoku.replacen("-e-", "-o-", 1), true).unwrap();
+        assert!(Puzzle::new_from_puzzle_file(sudoku_as_string).is_ok());
+    }
+
+    #[test]
+    fn check_kenken_with_givens() {
+        //without the givens, each row could be any permutation of 1,2,3
+        let raw_kenken = "KenKen with givens\nKenKen\n6+00.01.02\n6+10.11.12\n6+20.21.22\n\
+                          [Givens]\n-2-\n3--\n---"
+            .to_string();
+
+        for normal_group_direction in [true, false] {
+            let kenken_as_string =
+                PuzzleAsString::new_from_raw_string(raw_kenken.clone(), normal_group_direction)
+                    .unwrap();
+            let kenken = Puzzle::new_from_puzzle_file(kenken_as_string).unwrap();
+            assert_eq!(kenken.solution[1], 2);
+            let found_solution: Vec<usize> = kenken
+                .solve()
+                .unwrap()
+                .solution
+                .into_iter()
+                .filter(|&d| d != EMPTY)
+                .collect();
+            assert_eq!(found_solution, vec!(1, 2, 3, 3, 1, 2, 2, 3, 1));
+        }
+
+        for invalid_givens in ["-4-\n3--\n---", "-2-\n3--"] {
+            let invalid_kenken = PuzzleAsString::new_from_raw_string(
+                raw_kenken.replace("-2-\n3--\n---", invalid_givens),
+                true,
+            )
+            .and_then(Puzzle::new_from_puzzle_file);
+            assert!(invalid_kenken.is_err());
+        }
+    }
+
+    #[test]
+    fn check_zero_based_kenken() {
+        let raw_kenken = "Zero based KenKen\nKenKen\n6*00.01\n3+02.03.13\n1-10.11\n8+12.21.22\n\
+                          0*20.30.31\n1c23\n3-32.33\n[Digits]\n0123"
+            .to_string();
+
+        for normal_group_direction in [true, false] {
+            let kenken_as_string =
+                PuzzleAsString::new_from_raw_string(raw_kenken.clone(), normal_group_direction)
+                    .unwrap();
+            let found_solution: Vec<usize> = Puzzle::new_from_puzzle_file(kenken_as_string)
+                .unwrap()
+                .solve()
+                .unwrap()
+                .solution
+                .into_iter()
+                .filter(|&d| d != EMPTY)
+                .collect();
+            assert_eq!(
+                found_solution,
+                vec!(3, 2, 1, 0, 1, 0, 3, 2, 0, 3, 2, 1, 2, 1, 0, 3)
+            );
+        }
+
+        //the crusts of the sandwiches are the digits 0 and 3
+        for (sandwiches, is_solvable) in [("r0=3\nr1=0\nc0=1", true), ("r1=1", false)] {
+            let kenken_as_string = PuzzleAsString::new_from_raw_string(
+                format!("{}\n[Sandwiches]\n{}", raw_kenken, sandwiches),
+                true,
+            )
+            .unwrap();
+            let kenken = Puzzle::new_from_puzzle_file(kenken_as_string).unwrap();
+            assert_eq!(kenken.solve().is_some(), is_solvable, "{}", sandwiches);
+        }
+    }
+
+    #[test]
+    fn check_kakuro_solve() {
+        let kakuro_as_string = PuzzleAsString::new_from_file("KA-1.txt").unwrap();
+        let kakuro = Puzzle::new_from_puzzle_file(kakuro_as_string).unwrap();
+        assert_eq!(kakuro.groups.len(), 8);
+        assert_eq!(kakuro.black_positions.len(), 13);
+
+        let found_solution: Vec<usize> = kakuro
+            .solve()
+            .unwrap()
+            .solution
+            .into_iter()
+            .filter(|&d| d != EMPTY)
+            .collect();
+        assert_eq!(found_solution, vec!(1, 5, 9, 2, 8, 6, 2, 3, 4, 1, 1, 3));
+
+        //each white position must be covered by a clue
+        let missing_clue = PuzzleAsString::new_from_raw_string(
+            "Missing clue\nKakuro\n###\n#--\n#--\n[Clues]\n3v01\n4v02\n3>10".to_string(),
+            true,
+        )
+        .unwrap();
+        assert!(Puzzle::new_from_puzzle_file(missing_clue).is_ok());
+        let missing_clue = PuzzleAsString::new_from_raw_string(
+            "Missing clue\nKakuro\n###\n#--\n#--\n[Clues]\n3v01\n3>10".to_string(),
+            true,
+        )
+        .unwrap();
+        assert!(Puzzle::new_from_puzzle_file(missing_clue).is_err());
+    }
+
+    #[test]
+    fn check_str8ts_solve() {
+        let str8ts_as_string = PuzzleAsString::new_from_file("ST-1.txt").unwrap();
+        let str8ts = Puzzle::new_from_puzzle_file(str8ts_as_string).unwrap();
+        assert_eq!(str8ts.groups.len(), 6);
+
+        let found_solution: Vec<usize> = str8ts
+            .solve()
+            .unwrap()
+            .solution
+            .into_iter()
+            .filter(|&d| d != EMPTY)
+            .collect();
+        let expected_solution: Vec<usize> = "3126523456123445321564126523"
+            .chars()
+            .map(|c| c.to_digit(10).unwrap() as usize)
+            .collect();
+        assert_eq!(found_solution, expected_solution);
+    }
+
+    #[test]
+    fn check_inshi_no_heya_solve() {
+        let inshi_as_string = PuzzleAsString::new_from_file("IN-1.txt").unwrap();
+        let inshi = Puzzle::new_from_puzzle_file(inshi_as_string).unwrap();
+        assert_eq!(inshi.dimension, 6);
+
+        let found_solution: Vec<usize> = inshi
+            .solve()
+            .unwrap()
+            .solution
+            .into_iter()
+            .filter(|&d| d != EMPTY)
+            .collect();
+        let expected_solution: Vec<usize> =
+            "654231165342321564432615216453543126"
+                .chars()
+                .map(|c| c.to_digit(10).unwrap() as usize)
+                .collect();
+        assert_eq!(found_solution, expected_solution);
+
+        //rooms must be valid and cover each row exactly
+        for invalid_row in ["6(2)", "6(2) 4(2)", "6(2) 4(x)", "6(2) 4[1]", "6(2) 4(0) 4(1)"] {
+            let invalid_inshi = PuzzleAsString::new_from_raw_string(
+                format!("Invalid\nInshiNoHeya\n6(3)\n6(3)\n{}", invalid_row),
+                true,
+            )
+            .and_then(Puzzle::new_from_puzzle_file);
+            assert!(invalid_inshi.is_err());
+        }
+    }
+
+    #[test]
+    fn check_multi_sudoku_solve() {
+        let sudoku_as_string = PuzzleAsString::new_from_file("MS-Twin-1.txt").unwrap();
+        let sudoku = Puzzle::new_from_puzzle_file(sudoku_as_string).unwrap();
+        assert_eq!(sudoku.grid_offsets, vec!((0, 0), (6, 6)));
+        assert_eq!(sudoku.solution.len(), 200);
+        //the positions of the shared region are linked by constraints
+        assert!(!sudoku.constraints.is_empty());
+
+        let solved = sudoku.solve().unwrap();
+        let expected_display = [
+            "639251748      \n",
+            "458367912      \n",
+            "172849365      \n",
+            "583416297      \n",
+            "294738156      \n",
+            "761592483      \n",
+            "346125879456231\n",
+            "825974631892547\n",
+            "917683524731986\n",
+            "      157243698\n",
+            "      398165472\n",
+            "      246987315\n",
+            "      465378129\n",
+            "      982514763\n",
+            "      713629854\n",
+        ]
+        .concat();
+        assert_eq!(solved.get_multi_grid_display(&solved.solution), expected_display);
+    }
+
+    #[test]
+    fn check_anti_knight_sudoku() {
+        let sudoku_as_string = PuzzleAsString::new_from_file("S-AntiKnight-1.txt").unwrap();
+        let sudoku = Puzzle::new_from_puzzle_file(sudoku_as_string).unwrap();
+        assert!(!sudoku.constraints.is_empty());
+
+        for normal_group_direction in [true, false] {
+            let sudoku_as_string = PuzzleAsString::new_from_raw_string(
+                std::fs::read_to_string("S-AntiKnight-1.txt").unwrap(),
+                normal_group_direction,
+            )
+            .unwrap();
+            let solved = Puzzle::new_from_puzzle_file(sudoku_as_string).unwrap().solve().unwrap();
+            assert!(solved.get_violations().is_empty());
+            let found_solution: String = solved
+                .solution
+                .iter()
+                .filter(|&&d| d != EMPTY)
+                .map(|&d| solved.get_symbol(d))
+                .collect();
+            assert_eq!(
+                found_solution,
+                "639251748452687193187394265268935471745168932391742856916423587574816329823579614"
+            );
+        }
+
+        //a solution of the plain Sudoku, which violates the anti-knight rule
+        let mut checked = sudoku.clone();
+        let mut rows: Vec<String> = [
+            "639251748", "452687193", "187394265", "268935471", "745168932", "391742856",
+            "916423587", "574816329", "823579614",
+        ]
+        .iter()
+        .map(|row| row.to_string())
+        .collect();
+        rows[0] = "369251748".to_string();
+        let changed = checked.set_solution_from_rows(&rows).unwrap();
+        assert_eq!(changed, vec!("Position 00 must hold 6, found 3"));
+        let violations = checked.get_violations();
+        assert!(violations.iter().any(|v| v.starts_with("Repeated digits in column")));
+        assert!(violations.iter().any(|v| v.starts_with("Constraint '!'")));
+    }
+    #[test]
+    fn check_solution_rows_of_each_game_type() {
+        //the rows of each solution are read back into the unsolved puzzle without violations
+        for file_name in [
+            "KK-Dim4-1.txt",
+            "S-1.txt",
+            "F-1.txt",
+            "SK-1.txt",
+            "KA-1.txt",
+            "ST-1.txt",
+            "IN-1.txt",
+            "MS-Twin-1.txt",
+        ] {
+            let puzzle_as_string = PuzzleAsString::new_from_file(file_name).unwrap();
+            let mut puzzle = Puzzle::new_from_puzzle_file(puzzle_as_string).unwrap();
+            let rows = puzzle.solve().unwrap().get_solution_rows();
+            assert_eq!(puzzle.set_solution_from_rows(&rows), Ok(Vec::new()), "{}", file_name);
+            assert!(puzzle.get_violations().is_empty(), "{}", file_name);
+        }
+
+        //black positions and the rows of a multi grid Sudoku are checked as well
+        let kakuro_as_string = PuzzleAsString::new_from_file("KA-1.txt").unwrap();
+        let mut kakuro = Puzzle::new_from_puzzle_file(kakuro_as_string).unwrap();
+        let mut rows = kakuro.solve().unwrap().get_solution_rows();
+        rows[0].replace_range(0..1, "1");
+        assert!(kakuro.set_solution_from_rows(&rows).unwrap_err().contains("position 00"));
+
+        let sudoku_as_string = PuzzleAsString::new_from_file("MS-Twin-1.txt").unwrap();
+        let mut sudoku = Puzzle::new_from_puzzle_file(sudoku_as_string).unwrap();
+        let rows = sudoku.solve().unwrap().get_solution_rows();
+        let trimmed_rows: Vec<String> = rows.iter().map(|row| row.trim().to_string()).collect();
+        assert!(sudoku.set_solution_from_rows(&trimmed_rows[..14]).is_err());
+        assert_eq!(sudoku.set_solution_from_rows(&rows), Ok(Vec::new()));
+    }
 }
@@ -5,8 +5,11 @@ use crate::kk_load::PuzzleAsString;
 use crate::kk_puzzle::Puzzle;
 
 pub mod kk_block_list;
+pub mod kk_constraint;
+pub mod kk_diagnostic;
 pub mod kk_generate;
 pub mod kk_group;
+pub mod kk_html;
 pub mod kk_load;
 pub mod kk_puzzle;
 
@@ -1,4 +1,4 @@
-//! The program KenKen_Solver solves KenKen and Sudoku puzzles
+//! The program KenKen_Solver solves KenKen, Sudoku, Futoshiki, Skyscrapers, Kakuro, Str8ts and Inshi no heya puzzles
 //!
 //! The puzzle to solve must be specified a separate text-file with the following format
 //!
@@ -21,6 +21,12 @@
 //!     * '-' - subtraction, the cell must have exactly 2 fields
 //!     * ':' - division, the cell must have exactly 2 fields
 //!     * 'c' - constant, the cell has exactly 1 field with a given digit (which is the result)
+//! * a leading '!' marks a cell, whose digits must be distinct (killer-style),
+//!   otherwise digits might be repeated within a cell, if they are not in the same row or column
+//! * the rule "DistinctCages" after the game type, i.e. second line "KenKen DistinctCages",
+//!   requires distinct digits in all cells
+//! * the section "[Givens]" after the specification of the puzzle pre-fills digits within the cells,
+//!   it has one line per row with one digit per field ('-' for an empty field), e.g. "-2-"
 //! ## Examples
 //! for the KenKen puzzle [Newdoku puzzle 1278350](https://newdoku.com/include/online.php?id=1278350)
 //! ```
@@ -33,6 +39,63 @@
 //! 16*21.30.31
 //! 6+22.32.33
 //! ```
+//! ## Cage Map Format:
+//! Instead of the coordinates the cells might be drawn as a map
+//! * the lines after the game type have one character per field naming the cell of the field,
+//!   the fields of a cell must be connected horizontally or vertically
+//! * the section "[Cages]" has one line per cell with its name and its result and operation, e.g. "A 288*" or "B !12+"
+//!
+//! ```
+//! kenken_solver convert <path to puzzle> <map|list>
+//! ```
+//!
+//! converts a KenKen to the cage map respectively to the coordinate format.
+//! The conversion to the list loads and checks the whole puzzle first and writes it back as text,
+//! which works for all game types, e.g. to normalize a puzzle file. The text is built from the loaded puzzle,
+//! i.e. its given digits, its cages (runs of a Kakuro, rooms of an Inshi no heya), its constraints
+//! and its regions, so a puzzle edited by a program is saved the same way.
+//! The KenKen of the example above as cage map
+//! ```
+//! Newdoku.com KenKen-puzzle nr.: 1278350 with Dim 4 x 4
+//! KenKen
+//! AABB
+//! CCBD
+//! CEFD
+//! EEFF
+//! [Cages]
+//! A 1-
+//! B 8+
+//! C 6*
+//! D 2-
+//! E 16*
+//! F 6+
+//! ```
+//!
+//! ## Keen Game IDs:
+//! KenKen puzzles of the puzzle "Keen" of [Simon Tatham's Portable Puzzle Collection](https://www.chiark.greenend.org.uk/~sgtatham/puzzles/)
+//! are given by their game ID, e.g. "4:ab_4a3_2ba2_,s1a8m6s2m16a6" for the KenKen of the example above
+//! * the game ID is solved directly instead of a file, e.g. kenken_solver solve 4:ab_4a3_2ba2_,s1a8m6s2m16a6
+//! * a file might hold one game ID per line, optionally followed by a description (see KK-Keen.txt),
+//!   lines starting with '#' are comments
+//! * the parameters before ':' (e.g. "6dn") are kept in the header "keen params"
+//!
+//! ```
+//! kenken_solver convert <path to puzzle> keen
+//! ```
+//!
+//! prints a KenKen as Keen game ID, Keen has no distinct cages, rules, givens or other sections.
+//!
+//! ## Saved newdoku.com Pages:
+//! A KenKen page of [newdoku.com](https://newdoku.com) saved by the browser (file starting with '<') is imported directly,
+//! e.g. kenken_solver solve page.html or kenken_solver convert page.html list
+//! * the first table with n x n cells (n from 3 to 9) is the puzzle grid
+//! * cage borders are recognized by inline border widths (2px and more, "medium" or "thick") or by classes naming
+//!   the side together with "thick", "bold" or "cage"
+//! * clues use +, -, x, *, /, :, &times;, &divide; or &minus;, a clue without operation is a constant
+//! * the parts of a clue in inline tags are joined, e.g. <span>16</span>&times; is the clue 16*
+//! * the puzzle id of the page ("?id=") is taken into the description
+//! * KK-Newdoku-1.html is an example in the layout of a saved page
+//!
 //!# Sudoku:
 //! for more information about Sudoku see [Sudoku Wikipedia](https://de.wikipedia.org/wiki/Sudoku)
 //!
@@ -61,21 +124,531 @@
 //! -4-.-8-.---
 //! ```
 //!
+//! ## One Line Format:
+//! Sudokus are also given in the common one line format, i.e. the 81 positions row by row
+//! with the digits 1 to 9 and "." or "0" for open positions, optionally followed by a description
+//! * a file might hold a batch of Sudokus with one Sudoku per line, each is solved on its own
+//! * lines starting with "#" are comments
+//!
+//! ```
+//! # Sudokus in one line format, '.' or '0' for open positions
+//! .5...8269..2.43.....9........7..........9..4.5.3....9.....246.56.......3.4..8.... Sudoku Expert - https://sudoku.com/de/experte/
+//! 290003004006520003000070090010000006078000450500000080020090000400062300700100025
+//! ```
+//!
+//! ## Jigsaw Sudoku:
+//! A Sudoku with irregular regions instead of the 3x3 quadrants is specified by an additional
+//! section "[Regions]" after the rows of the puzzle
+//! * each line of the section is a row of the region map
+//! * each character names the region of the position, e.g. the digits 1 to 9
+//! * there must be exactly 9 regions with 9 connected positions each
+//!
+//! ```
+//! Jigsaw Sudoku
+//! Sudoku
+//! --48-27--
+//! -1-------
+//! ----6--4-
+//! ---138---
+//! 8--7-----
+//! --12-53--
+//! 27---98--
+//! --234----
+//! --------2
+//! [Regions]
+//! 111113333
+//! 122222233
+//! 111225333
+//! 444425666
+//! 445555566
+//! 445756666
+//! 477779999
+//! 778888999
+//! 778888899
+//! ```
+//!
+//! ## Hyper Sudoku (Windoku) and Extra Regions:
+//! Additional regions, whose digits must be distinct, are added to Sudoku and KenKen puzzles
+//! * the rule "Hyper" after the game type, i.e. second line "Sudoku Hyper", adds the four 3x3 regions
+//!   with the upper left corners 11, 15, 51 and 55
+//! * each line of an additional section "[Extra Regions]" after the specification of the puzzle
+//!   holds the fields of one region separated by '.', e.g. "00.11.22.33" for the diagonal of a 4x4 KenKen,
+//!   a region has at most as many fields as the puzzle has digits
+//!
+//! ```
+//! Hyper Sudoku (Windoku), four additional 3x3 regions of distinct digits
+//! Sudoku Hyper
+//! -4---871-
+//! 35------4
+//! --1-2-6--
+//! 5----9--6
+//! -6-7-3---
+//! ---6-4---
+//! --4-9-1--
+//! --814--9-
+//! 9-----2--
+//! ```
+//!
+//! ## Multi grid Sudoku (Samurai, Twin, Butterfly):
+//! Several 9x9 Sudoku grids sharing positions are specified with the game type "MultiSudoku"
+//! * third line till the section "[Grids]": the rows of the complete puzzle,
+//!     * given digits as digits,
+//!     * open fields are represented as "-"
+//!     * positions outside of all grids are represented as "#"
+//! * each line of the section "[Grids]" is the offset of one 9x9 grid, i.e. the row and the column
+//!   of its upper left corner within the rows of the puzzle, separated by a blank
+//! * positions covered by several grids are shared, i.e. hold the same digit in all these grids
+//! * an optional section "[Regions]" applies to all grids
+//! * a Samurai Sudoku has the grid offsets "0 0", "0 12", "6 6", "12 0" and "12 12"
+//!
+//! ```
+//! Twin Sudoku, two grids sharing one region
+//! MultiSudoku
+//! --9--1---######
+//! 4--3----2######
+//! 172----65######
+//! 5-----29-######
+//! 29--38-56######
+//! ---59-48-######
+//! 34------945---1
+//! -2-9---3-89----
+//! --768-------98-
+//! ######15--4-69-
+//! ######-981---7-
+//! ######2----73--
+//! ######4---7--2-
+//! ######9---1--63
+//! ######71-------
+//! [Grids]
+//! 0 0
+//! 6 6
+//! ```
+//!
+//! # Futoshiki:
+//! for more information about Futoshiki see [Futoshiki Wikipedia](https://en.wikipedia.org/wiki/Futoshiki)
+//!
+//! ## File Format:
+//! * first line comment
+//! * second line: must start with "Futoshiki" (exactly)
+//! * third line till the section "[Inequalities]": the rows of the puzzle,
+//!     * given digits as digits,
+//!     * open fields are represented as "-"
+//! * the dimension (3 to 9) is the number of rows, each row must have one character per column
+//! * each line of the section "[Inequalities]" is the relation between two neighbouring fields
+//!   ``` [field 1][relation][field 2] ```
+//!     * the fields are coordinates as for KenKen
+//!     * the relation is '<' or '>'
+//!
+//! ## Inequalities in KenKen and Sudoku
+//! The section "[Inequalities]" might be added to KenKen and Sudoku files as well.
+//!
+//! ## Kropki Dots and XV Clues
+//! The section "[Adjacent]" adds clues between two neighbouring fields to KenKen, Sudoku or Futoshiki files
+//! ``` [field 1][clue][field 2] ```
+//! * the fields are coordinates as for KenKen
+//! * the clue is one of the following
+//!     * 'w' - white dot, the digits are consecutive
+//!     * 'b' - black dot, one digit is the double of the other
+//!     * 'X' - the digits sum up to 10
+//!     * 'V' - the digits sum up to 5
+//!
+//! ```
+//! KenKen with adjacent clues
+//! KenKen
+//! 6+00.01.02
+//! 6+10.11.12
+//! 6+20.21.22
+//! [Adjacent]
+//! 00b01
+//! 01V02
+//! 10w11
+//! ```
+//!
+//! ## Sandwich, Thermometer and Arrow Clues
+//! The following sections add line and outside clues to KenKen, Sudoku or Futoshiki files, one clue per line
+//! * "[Sandwiches]" - the sum of the digits between the lowest and the highest digit (1 and 9 in a Sudoku)
+//!   of a row or column ``` [r|c][index]=[sum] ```, e.g. "r0=15" for the first row or "c8=0" for the last column
+//! * "[Thermometers]" - the digits strictly increase from the bulb to the tip of the thermometer,
+//!   the fields of the thermometer starting with the bulb separated by ".", e.g. "00.01.12"
+//! * "[Arrows]" - the digit of the circle is the sum of the digits along the arrow,
+//!   the fields of the arrow starting with the circle separated by ".", e.g. "00.01.12"
+//! * the fields of thermometers and arrows must be neighbours (including diagonals)
+//!
+//! ```
+//! KenKen with line clues
+//! KenKen
+//! 10+00.01.02.03
+//! 10+10.11.12.13
+//! 10+20.21.22.23
+//! 10+30.31.32.33
+//! [Sandwiches]
+//! r2=5
+//! c2=0
+//! [Thermometers]
+//! 22.13.02
+//! [Arrows]
+//! 21.30.31
+//! ```
+//!
+//! ## Examples
+//! ```
+//! Futoshiki 5 x 5 with one given digit
+//! Futoshiki
+//! -----
+//! --2--
+//! -----
+//! -----
+//! -----
+//! [Inequalities]
+//! 00>10
+//! 12<22
+//! 13<12
+//! 14<24
+//! 20>21
+//! 32<31
+//! 40>41
+//! 43<44
+//! ```
+//!
+//! # Skyscrapers:
+//! for more information about Skyscrapers see [Skyscrapers Wikipedia](https://en.wikipedia.org/wiki/Skyscraper_(puzzle))
+//!
+//! ## File Format:
+//! * first line comment
+//! * second line: must start with "Skyscrapers" (exactly)
+//! * third to sixth line: the clues outside the grid in the order top, bottom, left, right
+//!     * top and bottom have one clue per column from left to right
+//!     * left and right have one clue per row from top to bottom
+//!     * each clue is the count of visible skyscrapers as digit, missing clues are represented as "-"
+//! * the dimension (3 to 9) is the number of clues per line
+//!
+//! ## Examples
+//! ```
+//! Skyscrapers 5 x 5
+//! Skyscrapers
+//! --1-2
+//! --24-
+//! 33---
+//! --14-
+//! ```
+//!
+//! # Kakuro:
+//! for more information about Kakuro see [Kakuro Wikipedia](https://en.wikipedia.org/wiki/Kakuro)
+//!
+//! ## File Format:
+//! * first line comment
+//! * second line: must start with "Kakuro" (exactly)
+//! * third line till the section "[Clues]": the rows of the puzzle,
+//!     * black positions are represented as "#"
+//!     * white (open) positions are represented as "-"
+//! * the dimension (3 to 9) is the number of rows, each row must have one character per column
+//! * each line of the section "[Clues]" is the sum of a run of white positions
+//!   ``` [sum][direction][field] ```
+//!     * the field is the black position holding the clue, coordinates as for KenKen
+//!     * the direction is '>' for the run to the right (across) or 'v' for the run below (down)
+//! * each white position must be part of at least one run, the digits 1 to 9 must be distinct within a run
+//!
+//! ## Examples
+//! ```
+//! Kakuro 5 x 5
+//! Kakuro
+//! #####
+//! #--##
+//! #----
+//! #----
+//! ##--#
+//! [Clues]
+//! 12v01
+//! 11v02
+//! 6>10
+//! 15v13
+//! 7v14
+//! 25>20
+//! 10>30
+//! 4>41
+//! ```
+//!
+//! # Str8ts:
+//! for more information about Str8ts see [Str8ts Wikipedia](https://en.wikipedia.org/wiki/Str8ts)
+//!
+//! ## File Format:
+//! * first line comment
+//! * second line: must start with "Str8ts" (exactly)
+//! * third line till the section "[Black]": the rows of the puzzle,
+//!     * given digits (on white or black positions) as digits,
+//!     * open fields and black positions without digit are represented as "-"
+//! * the dimension (3 to 9) is the number of rows, each row must have one character per column
+//! * the section "[Black]" is a map of the same size, black positions are marked by "#",
+//!   white positions by "-"
+//! * the digits of each row and column must be distinct (including the given digits of black positions),
+//!   the white positions between two black positions of a row or column must form a straight,
+//!   i.e. a set of consecutive digits in any order
+//!
+//! ## Examples
+//! ```
+//! Str8ts 6 x 6
+//! Str8ts
+//! 3----5
+//! ---4--
+//! --3-4-
+//! -45--1
+//! ------
+//! 6---3-
+//! [Black]
+//! #--#--
+//! --#---
+//! ---#-#
+//! #-#---
+//! ---#--
+//! --#--#
+//! ```
+//!
+//! # Inshi no heya:
+//! for more information about Inshi no heya see [Inshi no heya Wikipedia](https://en.wikipedia.org/wiki/Inshi_no_heya)
+//!
+//! ## File Format:
+//! * first line comment
+//! * second line: must start with "InshiNoHeya" (exactly)
+//! * third line till end of file: one line per row with the rooms of the row from left to right,
+//!   separated by blanks
+//! * each room is given by the product of its digits and its length, e.g. "12(2)" for a room of two positions
+//!   with the product 12, the rooms of a row must cover all positions of the row
+//! * the dimension (3 to 9) is the number of rows, the digits of each row and column must be distinct
+//!
+//! ## Examples
+//! ```
+//! Inshi no heya, each room holds digits with the given product
+//! InshiNoHeya
+//! 30(2) 8(2) 3(2)
+//! 1(1) 30(2) 24(3)
+//! 3(1) 10(3) 6(1) 4(1)
+//! 24(3) 30(3)
+//! 12(3) 20(2) 3(1)
+//! 20(2) 3(2) 12(2)
+//! ```
+//!
+//! A new Inshi no heya is generated with
+//!
+//! ```
+//! kenken_solver generate_inshi <dimension> <difficulty>
+//! ```
+//!
+//! # Chess Rules:
+//! The rules "AntiKnight" and "AntiKing" after the game type (e.g. second line "Sudoku AntiKnight")
+//! forbid equal digits in positions a knight's or king's move apart.
+//! The rules can be combined with all puzzles, for multi grid Sudokus they apply within each grid.
+//!
+//! ## Examples
+//! ```
+//! Anti-knight Sudoku, no equal digits a knight's move apart
+//! Sudoku AntiKnight
+//! 6------4-
+//! -------9-
+//! -------65
+//! ----3--7-
+//! ---------
+//! 39--4--5-
+//! 9---2--8-
+//! -748---2-
+//! -2---9---
+//! ```
+//!
+//! # Odd and Even Positions:
+//! Shaded positions holding only even (or odd) digits are specified by the section "[Parity]"
+//! after the specification of the puzzle. The section holds a map with one line per row and
+//! one character per position: 'e' marks an even position, 'o' an odd position and '-' all others.
+//! A parity map can be used for all puzzles except multi grid Sudokus, 0 counts as even digit.
+//! The digits of the wrong parity are blocklisted for the shaded positions, so the solver never tries them.
+//! Given digits of the wrong parity are rejected when loading, the check mode reports them as violations.
+//!
+//! ## Examples
+//! ```
+//! KenKen with parity map
+//! KenKen
+//! 6+00.01.02
+//! 6+10.11.12
+//! 6+20.21.22
+//! [Inequalities]
+//! 00<01
+//! [Parity]
+//! -e-
+//! e--
+//! ---
+//! ```
+//!
+//! # Metadata Headers:
+//! Optional header lines "key: value" between the description in the first line and the game type
+//! describe the puzzle, e.g. its source, author, id, difficulty or date.
+//! The keys might contain letters, digits, spaces, '_' and '-', they are compared case insensitive
+//! (a key must not be given twice) but kept as written.
+//! * the header "rules" adds the given rules to the rules after the game type, e.g. "rules: AntiKnight Hyper"
+//! * the header "expected solution" (or short "solution") holds the expected solution with the rows
+//!   separated by ',', the solve mode compares the found solution with it and reports a mismatch
+//!
+//! The headers are shown by the solve and check modes and kept in their order by the to_json and convert modes.
+//!
+//! ## Examples
+//! ```
+//! Newdoku.com KenKen-puzzle nr.: 1278350 with Dim 4 x 4
+//! Source: newdoku.com
+//! id: 1278350
+//! difficulty: easy
+//! expected solution: 2341,1234,3412,4123
+//! KenKen
+//! 1-00.01
+//! 8+02.03.12
+//! 6*10.11.20
+//! 2-13.23
+//! 16*21.30.31
+//! 6+22.32.33
+//! ```
+//!
+//! # Problems in Puzzle Files:
+//! Before loading, the text of a puzzle file is checked and all problems are reported at once,
+//! each with its line and column, the offending text and a suggestion, e.g.
+//!
+//! ```
+//! line 2, column 8: Unknown rule 'DistinctCage' - did you mean 'DistinctCages'?
+//! line 4, column 4: No valid operation 'x' - use one of '+', '-', '*', ':' or 'c' after the result
+//! ```
+//!
+//! The check covers the game type, the rules, the section names, the lines of the puzzle
+//! for each game type (e.g. the cages of a KenKen, the rows of a Sudoku, the clues of a Skyscrapers
+//! or the rooms of an Inshi no heya) and the lines of each section (e.g. the constraints,
+//! the parity map, the region map or the grid offsets).
+//!
+//! The cages of a KenKen are checked as a whole before solving: positions outside of the grid,
+//! cages whose positions aren't connected, positions in several cages or in no cage
+//! and subtraction, division or constant cages with a wrong count of positions are reported per cage.
+//! The grid has the dimension of the header "dimension", if given, otherwise the largest row or column.
+//!
+//! ```
+//! kenken_solver lint <path to puzzle>
+//! ```
+//!
+//! reports all problems of a puzzle file without solving it, i.e. the problems of the text format,
+//! the problems of the cages and any other reason the puzzle can't be loaded (e.g. given digits
+//! contradicting a constraint). The exit status is 1 if problems are found, so lint can be used in scripts.
+//!
+//! # Checking a Solution:
+//! A solution (e.g. found by hand) is checked against all rules of the puzzle with
+//!
+//! ```
+//! kenken_solver check <path to puzzle> <path to solution>
+//! ```
+//!
+//! The solution file has one line per row with the symbols of the digits,
+//! all violated rules (e.g. repeated digits, unfulfilled groups or chess rules) are reported.
+//!
+//! # JSON Format:
+//! Puzzles and solutions are also read and written as JSON, e.g. for a web frontend.
+//! A puzzle file starting with "{" holds one puzzle object, a file starting with "[" an array of puzzles.
+//!
+//! ```
+//! kenken_solver to_json <path to puzzle>
+//! kenken_solver solve <path to puzzle> json
+//! ```
+//!
+//! prints the puzzle respectively its solution as JSON, the check mode accepts JSON solutions.
+//! A loaded puzzle (convert with "json") and the generated puzzles are written in the same format,
+//! the internal state of the solver, e.g. the options of the cages, isn't serialized.
+//! In the library these are Puzzle::to_json, GeneratedPuzzle::to_json and GeneratedSudoku::to_json,
+//! a single group (cage) has no JSON writer of its own, it is written as one of the "cages" of its puzzle.
+//!
+//! A puzzle object has the following fields
+//! * "game_type" (required): the game type as in the second line of a puzzle file, e.g. "KenKen" or "Sudoku"
+//! * "description": the comment of the first line of a puzzle file
+//! * "rules": the rules after the game type, e.g. ["DistinctCages"]
+//! * "dimension": the dimension of the puzzle, checked against the puzzle when reading
+//! * "cages": the cages of a KenKen, each with "result", "operation" (one of "+", "-", "*", ":", "c"),
+//!   "cells" (a list of [row, column]) and "distinct" (optional, true for a cage with a leading "!")
+//! * "rows": the lines of the specification of all other puzzles, e.g. the rows of a Sudoku
+//! * "givens": the rows of the section "[Givens]" of a KenKen
+//! * "sections": all other sections by name, each with its lines, e.g. {"Inequalities": ["00<01"]}
+//! * "metadata": additional information as strings by key, e.g. {"author": "..."}
+//!
+//! A solution object has the fields "game_type", "dimension" and "rows" (one string per row).
+//!
+//! ```
+//! {"description":"KenKen with givens","game_type":"KenKen","dimension":3,
+//!  "cages":[{"result":6,"operation":"+","cells":[[0,0],[0,1],[0,2]],"distinct":true},
+//!           {"result":6,"operation":"+","cells":[[1,0],[1,1],[1,2]]},
+//!           {"result":6,"operation":"+","cells":[[2,0],[2,1],[2,2]]}],
+//!  "givens":["-2-","3--","---"]}
+//!
+//! {"dimension":3,"game_type":"KenKen","rows":["123","312","231"]}
+//! ```
+//!
+//! # Pipelines:
+//! The path "-" reads the puzzle from stdin, so the solver is used in Unix pipelines,
+//! e.g. with the generator or other tools. The optional last parameter of the modes solve, gen_solve,
+//! check, lint and of the generators chooses the format of the results written to stdout
+//! * "text" (default): the human readable output, e.g. a puzzle file or the puzzle with its solution
+//! * "json": JSON objects as described above, one per line, like the modes to_json and convert with "json"
+//! * "rows": only the rows of each solution followed by an empty line (solve and gen_solve),
+//!   as read by the check mode
+//!
+//! Errors and unsolvable puzzles are reported on stderr in the formats "json" and "rows".
+//! The modes solve and gen_solve end with exit status 1 if a puzzle (of a batch) isn't solvable.
+//! A puzzle that can't be loaded or converted ends every mode with a message on stderr and exit status 1,
+//! a solution that can't be read is the only violation of the JSON result of the check mode.
+//! The check mode reads either the puzzle or the solution from stdin, its JSON result has the fields
+//! "valid" and "violations", the JSON result of lint has the field "problems".
+//!
+//! ```
+//! kenken_solver generate 6 2 1 | kenken_solver solve - rows
+//! kenken_solver generate_jigsaw 1 json | kenken_solver solve - json
+//! kenken_solver solve KK-Dim4-1.txt rows | kenken_solver check KK-Dim4-1.txt - json
+//! cat KK-Keen.txt | kenken_solver solve - rows
+//! ```
+//!
+//! # Digits and Symbols:
+//! By default a puzzle of dimension n uses the digits 1 to n. Other digit sets are specified by
+//! additional sections after the specification of the puzzle
+//! * the section "[Digits]" has one line with the digits of the puzzle, e.g. "0123" for a
+//!   zero based 4 x 4 KenKen, the count of digits must be the dimension
+//! * the section "[Symbols]" has one line with one character per digit (in ascending order of the digits),
+//!   which is used instead of the digit in the rows of Sudoku or Futoshiki files and in the solution,
+//!   e.g. "ABCDEFGHI" for a Sudoku with letters
+//!
+//! In a zero based KenKen each multiplication cage containing a 0 has the result 0,
+//! division cages must not contain a 0.
+//!
+//! ## Examples
+//! ```
+//! Zero based KenKen 4 x 4
+//! KenKen
+//! 6*00.01
+//! 3+02.03.13
+//! 1-10.11
+//! 8+12.21.22
+//! 0*20.30.31
+//! 1c23
+//! 3-32.33
+//! [Digits]
+//! 0123
+//! ```
+//!
 
 #[macro_use]
 extern crate derive_getters;
 
 mod kk_block_list;
+mod kk_constraint;
+mod kk_diagnostic;
 mod kk_generate;
 mod kk_group;
+mod kk_html;
 mod kk_load;
 mod kk_puzzle;
 
 
 
-use crate::kk_generate::GeneratedPuzzle;
-use crate::kk_load::PuzzleAsString;
+use crate::kk_generate::{GeneratedPuzzle, GeneratedSudoku};
+use crate::kk_load::{get_solution_rows_from_json, is_keen_id, read_input, PuzzleAsString};
+use serde_json::json;
 use std::env;
+use std::process;
 use std::time::Instant;
 
 use crate::kk_puzzle::Puzzle;
@@ -98,33 +671,251 @@ fn main() {
     } else {
         match &args[1][0..] {
             "solve" => solve(args),
-            "generate" => drop(generate(args)),
+            "check" => check(args),
+            "generate" => generate(args, &[OutputFormat::Text, OutputFormat::Json])
+                .into_iter()
+                .for_each(|(new_puzzle, format)| {
+                    print_puzzle(new_puzzle.to_raw_string(), || new_puzzle.to_json(), format)
+                }),
+            "generate_jigsaw" => generate_jigsaw(args),
+            "generate_inshi" => generate_inshi(args),
             "gen_solve" => gen_solve(args),
+            "to_json" => to_json(args),
+            "convert" => convert(args),
+            "lint" => lint(args),
             _ => help(),
         }
     }
 }
 
+/// The formats of the results written to stdout by the modes
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum OutputFormat {
+    /// human readable text, e.g. a puzzle file or the puzzle with its solution and the duration
+    Text,
+    /// JSON objects (see JSON Format), one per line
+    Json,
+    /// only the rows of a solution followed by an empty line, e.g. for the check mode
+    Rows,
+}
+
+/// Retrieves the output format from the optional last parameter of a mode,
+/// None if the parameter is no format supported by the mode
+fn get_output_format(arg: Option<&String>, formats: &[OutputFormat]) -> Option<OutputFormat> {
+    let format = match arg.map(|arg| arg.as_str()) {
+        None | Some("text") => OutputFormat::Text,
+        Some("json") => OutputFormat::Json,
+        Some("rows") => OutputFormat::Rows,
+        _ => return None,
+    };
+    formats.contains(&format).then_some(format)
+}
+
 fn solve(args: Vec<String>) {
+    let formats = [OutputFormat::Text, OutputFormat::Json, OutputFormat::Rows];
+    match get_output_format(args.get(3), &formats) {
+        Some(format) if (3..=4).contains(&args.len()) => {
+            //a file might hold a batch of Sudokus in the one line format or of JSON puzzles,
+            //a Keen game ID might be given directly instead of a file
+            let puzzles = if is_keen_id(&args[2]) {
+                PuzzleAsString::new_from_keen_id(&args[2], true).map(|puzzle| vec![puzzle])
+            } else {
+                PuzzleAsString::new_list_from_file(&args[2])
+            };
+            //all puzzles of a batch are solved, before an unsolvable puzzle ends with exit status 1
+            let unsolvable_count = puzzles
+                .unwrap_or_else(exit_with_load_error)
+                .into_iter()
+                .map(|puzzle_string| solve_kernel(puzzle_string, format))
+                .filter(|&is_solved| !is_solved)
+                .count();
+            if unsolvable_count > 0 {
+                process::exit(1);
+            }
+        }
+        _ => help(),
+    }
+}
+
+fn to_json(args: Vec<String>) {
     if args.len() != 3 {
         help();
+        return;
+    }
+    let json_puzzles: Vec<String> = PuzzleAsString::new_list_from_file(&args[2])
+        .unwrap_or_else(exit_with_load_error)
+        .iter()
+        .map(|puzzle_string| puzzle_string.to_json().unwrap_or_else(exit_with_conversion_error))
+        .collect();
+
+    if json_puzzles.len() == 1 {
+        println!("{}", json_puzzles[0]);
     } else {
-        solve_kernel(PuzzleAsString::new_from_file(&args[2]).expect("Couldn't load file."));
+        println!("[{}]", json_puzzles.join(",\n"));
+    }
+}
+
+fn convert(args: Vec<String>) {
+    if args.len() != 4 {
+        help();
+        return;
     }
+    let puzzle_string = PuzzleAsString::new_from_file(&args[2]).unwrap_or_else(exit_with_load_error);
+    //a KenKen given as cage map is already converted to the coordinate format when loading,
+    //the list is written after loading the whole puzzle, so it is checked completely
+    let converted = match &args[3][0..] {
+        "map" => puzzle_string.to_cage_map().unwrap_or_else(exit_with_conversion_error),
+        "keen" => puzzle_string.to_keen_id().unwrap_or_else(exit_with_conversion_error) + "\n",
+        "json" => Puzzle::new_from_puzzle_file(puzzle_string)
+            .unwrap_or_else(exit_with_load_error)
+            .to_json()
+            .unwrap_or_else(exit_with_conversion_error)
+            + "\n",
+        "list" => Puzzle::new_from_puzzle_file(puzzle_string)
+            .unwrap_or_else(exit_with_load_error)
+            .to_raw_string(),
+        _ => {
+            help();
+            return;
+        }
+    };
+    print!("{}", converted);
 }
 
-fn solve_kernel(puzzle_string: PuzzleAsString) {
+fn lint(args: Vec<String>) {
+    let format = match get_output_format(args.get(3), &[OutputFormat::Text, OutputFormat::Json]) {
+        Some(format) if (3..=4).contains(&args.len()) => format,
+        _ => {
+            help();
+            return;
+        }
+    };
+    //problems of the text format are reported when loading, the cages are checked afterwards
+    //one by one and finally the puzzle is initialized with all its rules
+    let problems: Vec<String> = match PuzzleAsString::new_from_file(&args[2]) {
+        Err(error) => vec![error],
+        Ok(puzzle_string) => match puzzle_string.get_cage_problems() {
+            cage_problems if !cage_problems.is_empty() => cage_problems,
+            _ => Puzzle::new_from_puzzle_file(puzzle_string).err().into_iter().collect(),
+        },
+    };
+    if format == OutputFormat::Json {
+        println!("{}", json!({ "problems": problems }));
+    } else if problems.is_empty() {
+        println!("No problems found");
+    } else {
+        problems.iter().for_each(|problem| println!("{}", problem));
+    }
+    if !problems.is_empty() {
+        process::exit(1);
+    }
+}
+
+fn check(args: Vec<String>) {
+    let format = match get_output_format(args.get(4), &[OutputFormat::Text, OutputFormat::Json]) {
+        Some(format) if (4..=5).contains(&args.len()) => format,
+        _ => {
+            help();
+            return;
+        }
+    };
+    //stdin holds either the puzzle or the solution
+    if args[2].trim() == "-" && args[3].trim() == "-" {
+        exit_with_load_error::<()>("Only one of puzzle and solution can be read from stdin".to_string());
+    }
+    let puzzle_string = PuzzleAsString::new_from_file(&args[2]).unwrap_or_else(exit_with_load_error);
+    let mut puzzle =
+        Puzzle::new_from_puzzle_file(puzzle_string).unwrap_or_else(exit_with_load_error);
+    let raw_solution = read_input(&args[3]).unwrap_or_else(exit_with_load_error);
+    //a solution is either given in the JSON format or as one line per row,
+    //leading blanks place the grids of a multi grid Sudoku
+    let rows: Result<Vec<String>, String> = if raw_solution.trim_start().starts_with('{') {
+        get_solution_rows_from_json(&raw_solution)
+    } else {
+        Ok(raw_solution
+            .lines()
+            .map(|row| row.trim_end().to_string())
+            .filter(|row| !row.is_empty())
+            .collect())
+    };
+
+    let violations = match rows.and_then(|rows| puzzle.set_solution_from_rows(&rows)) {
+        Ok(mut violations) => {
+            violations.append(&mut puzzle.get_violations());
+            violations
+        }
+        //an unreadable solution is the only violation of the JSON result
+        Err(error) if format == OutputFormat::Json => vec![error],
+        Err(error) => exit_with_error("Couldn't read the solution.", error),
+    };
+
+    if format == OutputFormat::Json {
+        println!("{}", json!({ "valid": violations.is_empty(), "violations": violations }));
+        return;
+    }
+    println!("Checked solution:\n\n{}\n", puzzle);
+    if violations.is_empty() {
+        println!("The solution is valid");
+    } else {
+        violations.iter().for_each(|violation| println!("{}", violation));
+    }
+}
+
+/// Prints the message and the problems (one per line) on stderr and exits with status 1
+fn exit_with_error<T>(message: &str, error: String) -> T {
+    eprintln!("{}\n{}", message, error);
+    process::exit(1);
+}
+
+/// Prints all problems found when loading a puzzle file one per line and exits
+fn exit_with_load_error<T>(error: String) -> T {
+    exit_with_error("Couldn't load file.", error)
+}
+
+/// Prints the reason why a puzzle can't be converted to the requested format and exits
+fn exit_with_conversion_error<T>(error: String) -> T {
+    exit_with_error("Couldn't convert the puzzle.", error)
+}
+
+/// Solves the puzzle and prints its solution in the given format,
+/// returns false, if the puzzle is not solvable
+fn solve_kernel(puzzle_string: PuzzleAsString, format: OutputFormat) -> bool {
     let now = Instant::now();
 
+    //in the JSON and the rows format only the solution is printed,
+    //a puzzle without solution is reported on stderr
+    if format != OutputFormat::Text {
+        let puzzle =
+            Puzzle::new_from_puzzle_file(puzzle_string).unwrap_or_else(exit_with_load_error);
+        match puzzle.solve() {
+            Some(solution) if format == OutputFormat::Json => println!("{}", solution.solution_to_json()),
+            Some(solution) => println!("{}\n", solution.get_solution_rows().join("\n")),
+            None => {
+                eprintln!("Error! Puzzle is not solvable!");
+                return false;
+            }
+        }
+        return true;
+    }
     println!("Starting to solve....\n{}", puzzle_string);
+    let expected_solution = puzzle_string.get_expected_solution().cloned();
 
-    let puzzle = Puzzle::new_from_puzzle_file(puzzle_string).expect("Init from loaded file failed");
+    let puzzle = Puzzle::new_from_puzzle_file(puzzle_string).unwrap_or_else(exit_with_load_error);
 
     //solve the puzzle and print out
     let solution_option = puzzle.solve();
+    let is_solved = solution_option.is_some();
     if solution_option.is_some() {
         let solution = solution_option.unwrap();
         println!("Solution: \n\n{}\n", solution);
+        //the expected solution of the header is compared ignoring separators between the rows
+        if let Some(expected_solution) = expected_solution {
+            if solution.matches_solution(&expected_solution) {
+                println!("The solution matches the expected solution");
+            } else {
+                println!("The solution differs from the expected solution {}", expected_solution);
+            }
+        }
     } else {
         println!("Error! Puzzle is not solvable!");
     }
@@ -136,43 +927,124 @@ fn solve_kernel(puzzle_string: PuzzleAsString) {
         duration / 1000 % 60,
         duration % 1000
     );
+    is_solved
 }
 
-fn generate(args: Vec<String>) -> String {
-    let mut new_puzzle_string: String = String::new();
-    if args.len() == 5 {
+/// Generates a new KenKen with the parameters of the mode generate (or gen_solve)
+/// and returns it together with the requested output format
+fn generate(args: Vec<String>, formats: &[OutputFormat]) -> Option<(GeneratedPuzzle, OutputFormat)> {
+    if (5..=8).contains(&args.len()) {
         let dimension: usize = args[2].parse().unwrap_or(100);
         let difficulty: usize = args[3].parse().unwrap_or(100);
         let operation_range: usize = args[4].parse().unwrap_or(100);
-        if dimension >= 3 && dimension <= 9 && difficulty <= 3 && operation_range <= 1 {
+        let distinct_digits = args[5..].iter().any(|arg| arg == "DistinctCages");
+        let zero_based = args[5..].iter().any(|arg| arg == "ZeroBased");
+        //the output format is the optional last parameter after the options
+        let options = args[5..].iter().filter(|arg| *arg == "DistinctCages" || *arg == "ZeroBased").count();
+        let format = get_output_format(args.get(5 + options), formats);
+        if dimension >= 3
+            && dimension <= 9
+            && difficulty <= 3
+            && operation_range <= 1
+            && args.len() <= 6 + options
+            && format.is_some()
+        {
             //println!("Generate {}x{} KenKen....\n------------------", dimension, dimension);
-            let new_puzzle =
-                GeneratedPuzzle::generate_kenken(dimension, difficulty, operation_range);
-            new_puzzle_string = new_puzzle.to_raw_string();
-            println!("{}", new_puzzle_string);
-        } else {
-            help();
+            let new_puzzle = if zero_based {
+                GeneratedPuzzle::generate_kenken_with_digits(
+                    &(0..dimension).collect::<Vec<usize>>(),
+                    difficulty,
+                    operation_range,
+                    distinct_digits,
+                )
+            } else {
+                GeneratedPuzzle::generate_kenken(
+                    dimension,
+                    difficulty,
+                    operation_range,
+                    distinct_digits,
+                )
+            };
+            return format.map(|format| (new_puzzle, format));
         }
-    } else {
-        help();
     }
+    help();
+    None
+}
 
-    new_puzzle_string
+/// Prints a generated puzzle as puzzle file or as JSON puzzle
+fn print_puzzle(
+    raw_puzzle_string: String,
+    to_json: impl FnOnce() -> Result<String, String>,
+    format: OutputFormat,
+) {
+    match format {
+        OutputFormat::Json => println!("{}", to_json().unwrap_or_else(exit_with_conversion_error)),
+        _ => println!("{}", raw_puzzle_string),
+    }
+}
+
+fn generate_jigsaw(args: Vec<String>) {
+    match get_output_format(args.get(3), &[OutputFormat::Text, OutputFormat::Json]) {
+        Some(format) if (3..=4).contains(&args.len()) => {
+            let difficulty: usize = args[2].parse().unwrap_or(100);
+            if difficulty <= 3 {
+                let new_puzzle = GeneratedSudoku::generate_jigsaw_sudoku(difficulty);
+                print_puzzle(new_puzzle.to_raw_string(), || new_puzzle.to_json(), format);
+            } else {
+                help();
+            }
+        }
+        _ => help(),
+    }
+}
+
+fn generate_inshi(args: Vec<String>) {
+    match get_output_format(args.get(4), &[OutputFormat::Text, OutputFormat::Json]) {
+        Some(format) if (4..=5).contains(&args.len()) => {
+            let dimension: usize = args[2].parse().unwrap_or(100);
+            let difficulty: usize = args[3].parse().unwrap_or(100);
+            if (3..=9).contains(&dimension) && difficulty <= 3 {
+                let new_puzzle = GeneratedPuzzle::generate_inshi_no_heya(dimension, difficulty);
+                print_puzzle(new_puzzle.to_raw_string(), || new_puzzle.to_json(), format);
+            } else {
+                help();
+            }
+        }
+        _ => help(),
+    }
 }
 
 fn gen_solve(args: Vec<String>) {
-    let puzzle_as_string = PuzzleAsString::new_from_raw_string(generate(args),true);
-    if puzzle_as_string.is_ok() {
-        solve_kernel(puzzle_as_string.unwrap());
+    let formats = [OutputFormat::Text, OutputFormat::Json, OutputFormat::Rows];
+    if let Some((new_puzzle, format)) = generate(args, &formats) {
+        let puzzle_as_string = PuzzleAsString::new_from_raw_string(new_puzzle.to_raw_string(), true)
+            .unwrap_or_else(exit_with_load_error);
+        if !solve_kernel(puzzle_as_string, format) {
+            process::exit(1);
+        }
     }
 }
 
 fn help() {
     println!("run mode [parameters] - starts KenKen-Solver in one of the following modes with the following parameters\n");
     println!("Modes:");
-    println!("solve <path to puzzle> - prints the solution of the specified puzzle");
-    println!("generate <dimension> <difficulty> <operations_range> - generates a new KenKen-puzzle with the given parameters\n");
+    println!("solve <path to puzzle or Keen game ID> [text|json|rows] - prints the solution of the specified puzzle (or of each puzzle of a batch file), optionally as JSON or only its rows");
+    println!("check <path to puzzle> <path to solution> [text|json] - checks the solution (one line per row or JSON) of the specified puzzle");
+    println!("to_json <path to puzzle> - prints the specified puzzle (or each puzzle of a batch file) as JSON");
+    println!("lint <path to puzzle> [text|json] - reports all problems of the specified puzzle file, e.g. disconnected or overlapping cages of a KenKen, and exits with status 1 if there are any");
+    println!("convert <path to puzzle> <map|list|keen|json> - prints the specified KenKen with its cages as letter map, as list of coordinates, as Keen game ID or as JSON");
+    println!("generate <dimension> <difficulty> <operations_range> [DistinctCages] [ZeroBased] [text|json] - generates a new KenKen-puzzle with the given parameters\n");
     println!("  dimension [3-9] - the dimension/size of the KenKen");
     println!("  difficulty [0-3] - the difficulty of the KenKen 0-easy to 3-expert");
     println!("  operations_range [0,1] - the used operations in the KenKen 0-only addition, 1 - all operations");
+    println!("  DistinctCages - optional, no digit is repeated within a cage");
+    println!("  ZeroBased - optional, the KenKen uses the digits 0 to dimension-1");
+    println!("generate_jigsaw <difficulty> [text|json] - generates a new jigsaw Sudoku with irregular regions\n");
+    println!("  difficulty [0-3] - the difficulty of the Sudoku 0-easy to 3-expert");
+    println!("generate_inshi <dimension> <difficulty> [text|json] - generates a new Inshi no heya with multiplication rooms\n");
+    println!("  dimension [3-9] - the dimension/size of the Inshi no heya");
+    println!("  difficulty [0-3] - the difficulty of the Inshi no heya 0-easy to 3-expert");
+    println!("gen_solve <parameters of generate> [text|json|rows] - generates a new KenKen-puzzle and prints its solution\n");
+    println!("The path \"-\" reads the puzzle (or the solution of check) from stdin, the last parameter chooses the output format");
 }